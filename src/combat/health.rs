@@ -1,4 +1,4 @@
-use crate::Game;
+use crate::{world, Game};
 
 /// A measure of hit points.
 ///
@@ -156,10 +156,27 @@ fn health_misc() {
 }
 
 /// Gives things with 0 health the Dead component.
-pub fn remove_out_of_health(Game { ecs, dead, .. }: &mut Game) {
+///
+/// The player is left alone; `world::player::death` takes care of them.
+pub fn remove_out_of_health(
+    Game {
+        ecs,
+        dead,
+        player,
+        stats,
+        instance_tracker,
+        config,
+        ..
+    }: &mut Game,
+) {
     for (ent, &health) in ecs.query::<&Health>().iter() {
-        if health.is_dead() {
+        if health.is_dead() && ent != player.entity && !dead.is_marked(ent) {
             dead.mark(ent);
+            stats.killed(world::stats::prefab_name(
+                instance_tracker,
+                &config.prefab,
+                ent,
+            ));
         }
     }
 }
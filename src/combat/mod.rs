@@ -1,7 +1,12 @@
 use crate::phys::PhysHandle;
+use fxhash::FxHashMap;
 
 pub mod health;
 pub use health::Health;
+pub mod resistance;
+pub use resistance::{DamageType, Resistances};
+pub mod status;
+pub use status::{StatusEffect, StatusEffects, StatusKind};
 
 /// Things with the Hurtful component remove Health from the Entities in their Contacts.
 ///
@@ -10,13 +15,18 @@ pub use health::Health;
 /// let default_hurtful = Hurtful {
 ///     raw_damage: 1.0,
 ///     kind: HurtfulKind::Raw,
+///     damage_type: DamageType::Blunt,
 ///     minimum_speed: 0.0
 ///     minimum_damage: 0
+///     invulnerability_frames: 0,
+///     rehit_interval: None,
+///     effects: vec![],
 /// };
 ///
 /// assert_eq!(default_hurtful, Hurtful::default())
 /// ```
-#[derive(Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct Hurtful {
     /// The damage before it is multipled by i.e. the speed as is the case if `HurtfulKind::Ram` is
     /// supplied.
@@ -24,36 +34,192 @@ pub struct Hurtful {
     /// Is this Entity always Hurtful, or is it only Hurtful when it's going at a certain speed?
     /// Or something else entirely?
     pub kind: HurtfulKind,
+    /// Entities with Resistances may take more or less damage depending on this.
+    #[serde(default)]
+    pub damage_type: DamageType,
     /// If the Hurtful Entity gets a Force component and it goes below this value then when
     /// it collides with anything, no damage will be dealt. If the speed picks back up, then
     /// damage will be dealt again.
     ///
     /// Supplying 0.0 means that the Hurtful component will never be limited due to low speeds.
     /// This is the default value.
+    #[serde(default)]
     pub minimum_speed: f32,
     /// Especially with HurtfulKind::Ram, it's easy to get *really close* to dealing some damage,
     /// but not quite. Here you can specify at least how much damage should be dealt.
+    #[serde(default)]
     pub minimum_damage: usize,
+    /// For how many frames the victim is Invulnerable after being hit by this.
+    #[serde(default)]
+    pub invulnerability_frames: u16,
+    /// If this Entity has a HitRecord, it won't deal damage to anything in it until
+    /// this many frames have passed since it was hit. None means never, at least until
+    /// the HitRecord is cleared, which happens e.g. when a weapon is thrown again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rehit_interval: Option<u16>,
+    /// StatusEffects applied to whatever this damages.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<StatusEffect>,
 }
 impl Default for Hurtful {
     fn default() -> Self {
         Self {
             raw_damage: 1.0,
             kind: HurtfulKind::Raw,
+            damage_type: DamageType::Blunt,
             minimum_speed: 0.0,
             minimum_damage: 0,
+            invulnerability_frames: 0,
+            rehit_interval: None,
+            effects: vec![],
         }
     }
 }
 impl Hurtful {
-    fn damage(&self, speed: f32) -> Health {
-        let calculated = (self.raw_damage * self.kind.damage_coefficient(speed)).round() as usize;
-        Health::new(calculated.max(self.minimum_damage))
+    /// Returns None if the Resistances make the victim immune to this Hurtful's damage.
+    fn damage(&self, speed: f32, resistances: Option<&Resistances>) -> Option<Health> {
+        let calculated =
+            (self.raw_damage * self.kind.damage_coefficient(speed)).max(self.minimum_damage as f32);
+        let resisted = match resistances {
+            Some(r) => r.resist(self.damage_type, calculated)?,
+            None => calculated,
+        };
+        Some(Health::new(resisted.round() as usize))
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.label("raw damage");
+        ui.add(egui::DragValue::f32(&mut self.raw_damage).speed(0.01));
+
+        let mut ram = matches!(self.kind, HurtfulKind::Ram { .. });
+        ui.checkbox("ram", &mut ram)
+            .tooltip_text("only hurts when moving, dealing more damage the faster it goes");
+        match (ram, &mut self.kind) {
+            (
+                true,
+                HurtfulKind::Ram {
+                    speed_damage_coefficient,
+                },
+            ) => {
+                ui.label("speed damage coefficient");
+                ui.add(egui::DragValue::f32(speed_damage_coefficient).speed(0.01));
+            }
+            (true, kind) => {
+                *kind = HurtfulKind::Ram {
+                    speed_damage_coefficient: 1.0,
+                }
+            }
+            (false, kind) => *kind = HurtfulKind::Raw,
+        }
+
+        ui.collapsing("damage type", |ui| {
+            for &dt in resistance::ALL_DAMAGE_TYPES {
+                ui.radio_value(format!("{:?}", dt), &mut self.damage_type, dt);
+            }
+        });
+
+        ui.label("minimum speed");
+        ui.add(egui::DragValue::f32(&mut self.minimum_speed).speed(0.001));
+
+        ui.label("minimum damage");
+        let mut md = self.minimum_damage as f32;
+        ui.add(egui::DragValue::f32(&mut md));
+        self.minimum_damage = md.max(0.0) as usize;
+
+        ui.label("invulnerability frames");
+        let mut inv = self.invulnerability_frames as f32;
+        ui.add(egui::DragValue::f32(&mut inv));
+        self.invulnerability_frames = inv.max(0.0).round() as u16;
+
+        let mut rehit = self.rehit_interval.is_some();
+        ui.checkbox("rehit interval", &mut rehit)
+            .tooltip_text("how many frames until the same thing can be hurt again");
+        self.rehit_interval = if rehit {
+            let mut ri = self.rehit_interval.unwrap_or(30) as f32;
+            ui.add(egui::DragValue::f32(&mut ri));
+            Some(ri.max(0.0).round() as u16)
+        } else {
+            None
+        };
+
+        ui.collapsing("status effects", |ui| {
+            let mut removal_index: Option<usize> = None;
+            for (i, effect) in self.effects.iter_mut().enumerate() {
+                ui.collapsing(format!("effect {}", i), |ui| {
+                    effect.dev_ui(ui);
+                    if ui.button("Remove").clicked {
+                        removal_index = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removal_index {
+                self.effects.remove(i);
+            }
+            if ui.button("Add Effect").clicked {
+                self.effects
+                    .push(StatusEffect::new(StatusKind::Slow, 60, 0.5));
+            }
+        });
+
+        *self != before
+    }
+}
+
+/// Entities with this component take no damage from Hurtfuls until the frames run out.
+#[derive(Debug, Clone, Copy)]
+pub struct Invulnerable {
+    pub frames: u16,
+}
+
+/// Hurtful Entities with a HitRecord remember who they've hit,
+/// and for how many frames ago, so that they can abide by their `rehit_interval`.
+#[derive(Debug, Clone, Default)]
+pub struct HitRecord(FxHashMap<hecs::Entity, u16>);
+impl HitRecord {
+    fn can_hit(&self, e: hecs::Entity) -> bool {
+        !self.0.contains_key(&e)
+    }
+
+    fn record(&mut self, e: hecs::Entity) {
+        self.0.insert(e, 0);
+    }
+}
+
+/// Counts down Invulnerable frames and ages the entries in HitRecords,
+/// removing them once they're no longer relevant.
+pub fn tick_hit_timers(world: &mut crate::Game) {
+    let ecs = &mut world.ecs;
+
+    let vulnerable: Vec<hecs::Entity> = ecs
+        .query::<&mut Invulnerable>()
+        .iter()
+        .filter_map(|(e, inv)| {
+            inv.frames = inv.frames.saturating_sub(1);
+            Some(e).filter(|_| inv.frames == 0)
+        })
+        .collect();
+    for e in vulnerable {
+        drop(ecs.remove_one::<Invulnerable>(e));
+    }
+
+    for (_, (record, hurtful)) in ecs.query::<(&mut HitRecord, &Hurtful)>().iter() {
+        record.0.retain(|&e, age| {
+            // nothing can be rehit once it's gone
+            if !ecs.contains(e) {
+                return false;
+            }
+            *age = age.saturating_add(1);
+            hurtful.rehit_interval.map_or(true, |i| *age < i)
+        });
     }
 }
 
 /// Control when your Entity is Hurtful
-#[derive(Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
 pub enum HurtfulKind {
     /// Do damage only if moving quickly and collision occurs with something.
     Ram {
@@ -88,13 +254,16 @@ pub fn hurtful_damage(world: &mut crate::Game) {
 
     let ecs = &world.ecs;
     let phys = &world.phys;
+    let mut newly_invulnerable = vec![];
+    let mut afflicted = vec![];
 
-    for (_, (contacts, &h, hurtful, force)) in ecs
+    for (hurtful_ent, (contacts, &h, hurtful, force, mut record)) in ecs
         .query::<(
             &collision::Contacts,
             &PhysHandle,
             &Hurtful,
             Option<&phys::Force>,
+            Option<&mut HitRecord>,
         )>()
         .iter()
     {
@@ -110,10 +279,42 @@ pub fn hurtful_damage(world: &mut crate::Game) {
         };
 
         for &touched_ent in contacts.iter() {
+            if ecs.get::<Invulnerable>(touched_ent).is_ok()
+                || record.as_ref().map_or(false, |r| !r.can_hit(touched_ent))
+            {
+                continue;
+            }
+
+            let resistances = ecs.get::<Resistances>(touched_ent).ok();
+            let damage = match hurtful.damage(speed, resistances.as_deref()) {
+                Some(damage) => damage,
+                None => continue,
+            };
+
             if let Ok(mut hp) = ecs.get_mut::<Health>(touched_ent) {
-                *hp -= hurtful.damage(speed);
+                *hp -= damage;
+                // thorns and enemies hurting each other don't count towards the player's stats
+                if world.player.wields(hurtful_ent) {
+                    world.stats.damaged(
+                        hurtful_ent,
+                        crate::world::stats::prefab_name(
+                            &world.instance_tracker,
+                            &world.config.prefab,
+                            touched_ent,
+                        ),
+                        *damage,
+                    );
+                }
+
+                if let Some(r) = record.as_mut() {
+                    r.record(touched_ent);
+                }
+                if hurtful.invulnerability_frames > 0 {
+                    newly_invulnerable.push((touched_ent, hurtful.invulnerability_frames));
+                }
+                afflicted.extend(hurtful.effects.iter().map(|&fx| (touched_ent, fx)));
 
-                (|| {
+                let hit = (|| {
                     let touched_h = *ecs.get(touched_ent).ok()?;
 
                     let mut emitter_pos = *phys.collision_object(touched_h)?.position();
@@ -129,9 +330,19 @@ pub fn hurtful_damage(world: &mut crate::Game) {
                         &na::Vector2::x_axis(),
                     );
 
-                    Some(())
+                    Some(emitter_pos)
                 })();
+                if let Some(emitter_pos) = hit {
+                    world.feedback.hit(touched_ent, emitter_pos, *damage);
+                }
             }
         }
     }
+
+    for (e, frames) in newly_invulnerable {
+        drop(world.ecs.insert_one(e, Invulnerable { frames }));
+    }
+    for (e, effect) in afflicted {
+        status::apply_status(&mut world.ecs, e, effect);
+    }
 }
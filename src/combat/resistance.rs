@@ -0,0 +1,91 @@
+use std::collections::{HashMap, HashSet};
+
+/// What sort of damage a Hurtful deals, so that some things can take more or less of it.
+#[derive(serde::Deserialize, serde::Serialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DamageType {
+    Blunt,
+    Pierce,
+    Slash,
+    Fire,
+    Ice,
+    Poison,
+}
+impl Default for DamageType {
+    fn default() -> Self {
+        DamageType::Blunt
+    }
+}
+
+#[cfg(feature = "confui")]
+pub const ALL_DAMAGE_TYPES: &[DamageType] = {
+    use DamageType::*;
+    &[Blunt, Pierce, Slash, Fire, Ice, Poison]
+};
+
+/// Entities with Resistances take more or less damage of certain types, or none at all.
+///
+/// Damage types that aren't mentioned are dealt as usual.
+#[derive(serde::Deserialize, serde::Serialize, Default, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Resistances {
+    /// Damage of these types is multiplied by the supplied amount before being dealt,
+    /// i.e. 0.5 halves the damage and 2.0 doubles it.
+    #[serde(default)]
+    pub multipliers: HashMap<DamageType, f32>,
+    /// Damage of these types is never dealt.
+    #[serde(default)]
+    pub immunities: HashSet<DamageType>,
+}
+impl Resistances {
+    /// Returns None if immune to this type of damage.
+    pub fn resist(&self, kind: DamageType, damage: f32) -> Option<f32> {
+        if self.immunities.contains(&kind) {
+            None
+        } else {
+            Some(damage * self.multipliers.get(&kind).copied().unwrap_or(1.0))
+        }
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        for &kind in ALL_DAMAGE_TYPES {
+            ui.horizontal(|ui| {
+                ui.label(format!("{:?}", kind));
+
+                let mut immune = self.immunities.contains(&kind);
+                ui.checkbox("immune", &mut immune);
+                if immune {
+                    self.immunities.insert(kind);
+                } else {
+                    self.immunities.remove(&kind);
+                }
+
+                let mut multiplier = self.multipliers.get(&kind).copied().unwrap_or(1.0);
+                ui.add(egui::DragValue::f32(&mut multiplier).speed(0.01));
+                if multiplier == 1.0 {
+                    self.multipliers.remove(&kind);
+                } else {
+                    self.multipliers.insert(kind, multiplier);
+                }
+            });
+        }
+
+        *self != before
+    }
+}
+
+#[test]
+fn resist() {
+    let mut res = Resistances::default();
+    assert_eq!(res.resist(DamageType::Blunt, 3.0), Some(3.0));
+
+    res.multipliers.insert(DamageType::Blunt, 0.5);
+    res.immunities.insert(DamageType::Fire);
+
+    assert_eq!(res.resist(DamageType::Blunt, 3.0), Some(1.5));
+    assert_eq!(res.resist(DamageType::Pierce, 3.0), Some(3.0));
+    assert_eq!(res.resist(DamageType::Fire, 3.0), None);
+}
@@ -0,0 +1,204 @@
+use super::Health;
+use crate::{world, Game};
+use glsp::FromVal;
+
+/// Poison deals its damage this often, in frames.
+const POISON_INTERVAL: u16 = 60;
+/// Burning is quicker, but usually weaker.
+const BURN_INTERVAL: u16 = 20;
+
+#[derive(serde::Deserialize, serde::Serialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum StatusKind {
+    /// Movement and Forces are scaled by the strength.
+    Slow,
+    /// Can't move on its own or run its script's `update`.
+    Stun,
+    /// Takes the strength in damage every so often.
+    Poison,
+    /// Like Poison, but quicker.
+    Burn,
+}
+impl FromVal for StatusKind {
+    fn from_val(val: &glsp::Val) -> glsp::GResult<Self> {
+        let sym = glsp::Sym::from_val(val)?;
+        Ok(match &*sym.name() {
+            "slow" => Self::Slow,
+            "stun" => Self::Stun,
+            "poison" => Self::Poison,
+            "burn" => Self::Burn,
+            _ => glsp::bail!("Not a valid status effect: {}", sym),
+        })
+    }
+}
+
+#[cfg(feature = "confui")]
+pub const ALL_STATUS_KINDS: &[StatusKind] = {
+    use StatusKind::*;
+    &[Slow, Stun, Poison, Burn]
+};
+
+/// A timed effect that changes how other systems treat an Entity.
+#[derive(serde::Deserialize, serde::Serialize, Copy, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// How many frames the effect lasts.
+    pub frames: u16,
+    /// What this means depends on the kind of effect, see `StatusKind`.
+    #[serde(default)]
+    pub strength: f32,
+    #[serde(skip, default)]
+    age: u16,
+}
+impl StatusEffect {
+    pub fn new(kind: StatusKind, frames: u16, strength: f32) -> Self {
+        Self {
+            kind,
+            frames,
+            strength,
+            age: 0,
+        }
+    }
+
+    /// The damage this effect deals this frame, if any.
+    fn tick_damage(&self) -> Option<usize> {
+        let interval = match self.kind {
+            StatusKind::Poison => POISON_INTERVAL,
+            StatusKind::Burn => BURN_INTERVAL,
+            _ => return None,
+        };
+        Some(self.strength.round() as usize).filter(|_| self.age > 0 && self.age % interval == 0)
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = *self;
+
+        for &kind in ALL_STATUS_KINDS {
+            ui.radio_value(format!("{:?}", kind), &mut self.kind, kind);
+        }
+
+        ui.label("frames");
+        let mut frames = self.frames as f32;
+        ui.add(egui::DragValue::f32(&mut frames));
+        self.frames = frames.max(0.0).round() as u16;
+
+        ui.label("strength");
+        ui.add(egui::DragValue::f32(&mut self.strength).speed(0.01));
+
+        *self != before
+    }
+}
+
+/// All of the StatusEffects currently afflicting an Entity.
+#[derive(Clone, Debug, Default)]
+pub struct StatusEffects(Vec<StatusEffect>);
+impl StatusEffects {
+    /// Applying an effect the Entity already has restarts it,
+    /// keeping whichever duration is longer.
+    pub fn apply(&mut self, effect: StatusEffect) {
+        match self.0.iter_mut().find(|e| e.kind == effect.kind) {
+            Some(existing) => {
+                existing.frames = existing
+                    .frames
+                    .saturating_sub(existing.age)
+                    .max(effect.frames);
+                existing.strength = effect.strength;
+                existing.age = 0;
+            }
+            None => self.0.push(effect),
+        }
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|e| e.kind == kind)
+    }
+
+    pub fn remove(&mut self, kind: StatusKind) {
+        self.0.retain(|e| e.kind != kind);
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.has(StatusKind::Stun)
+    }
+
+    /// Speeds and Forces should be multiplied by this.
+    pub fn slow_factor(&self) -> f32 {
+        self.0
+            .iter()
+            .filter(|e| e.kind == StatusKind::Slow)
+            .map(|e| e.strength.max(0.0))
+            .fold(1.0, f32::min)
+    }
+
+    /// How to tint the sprite of an Entity with these effects, if at all.
+    pub fn tint(&self) -> Option<macroquad::Color> {
+        use macroquad::Color;
+        use StatusKind::*;
+
+        // the most debilitating effects show up first
+        [Stun, Burn, Poison, Slow]
+            .iter()
+            .find(|&&k| self.has(k))
+            .map(|k| match k {
+                Stun => Color([255, 240, 120, 255]),
+                Burn => Color([255, 150, 80, 255]),
+                Poison => Color([150, 255, 130, 255]),
+                Slow => Color([140, 180, 255, 255]),
+            })
+    }
+}
+
+/// Puts the effect on the Entity, giving it StatusEffects if it doesn't have them already.
+pub fn apply_status(ecs: &mut hecs::World, e: hecs::Entity, effect: StatusEffect) {
+    if let Ok(mut effects) = ecs.get_mut::<StatusEffects>(e) {
+        effects.apply(effect);
+        return;
+    }
+
+    let mut effects = StatusEffects::default();
+    effects.apply(effect);
+    drop(ecs.insert_one(e, effects));
+}
+
+/// Ages StatusEffects, removing them when they run out and dealing any damage they're meant to.
+/// Also lets scripts know which Entities are stunned.
+pub fn tick_status_effects(Game { ecs, .. }: &mut Game) {
+    let mut scripts = glsp::lib_mut::<world::script::Cache>();
+
+    for (e, (StatusEffects(effects), mut hp)) in ecs
+        .query::<(&mut StatusEffects, Option<&mut Health>)>()
+        .iter()
+    {
+        for effect in effects.iter_mut() {
+            effect.age += 1;
+            if let (Some(damage), Some(hp)) = (effect.tick_damage(), hp.as_mut()) {
+                **hp -= Health::new(damage);
+            }
+        }
+        effects.retain(|e| e.age < e.frames);
+
+        if effects.iter().any(|e| e.kind == StatusKind::Stun) {
+            scripts.stunned.insert(e);
+        }
+    }
+}
+
+#[test]
+fn status_apply() {
+    let mut effects = StatusEffects::default();
+    assert_eq!(effects.slow_factor(), 1.0);
+
+    effects.apply(StatusEffect::new(StatusKind::Slow, 10, 0.5));
+    effects.apply(StatusEffect::new(StatusKind::Slow, 5, 0.25));
+    assert_eq!(effects.0.len(), 1);
+    assert_eq!(effects.0[0].frames, 10);
+    assert_eq!(effects.slow_factor(), 0.25);
+
+    assert!(!effects.is_stunned());
+    effects.apply(StatusEffect::new(StatusKind::Stun, 5, 0.0));
+    assert!(effects.is_stunned());
+    effects.remove(StatusKind::Stun);
+    assert!(!effects.is_stunned());
+}
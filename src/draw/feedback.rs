@@ -0,0 +1,141 @@
+//! Lets the player know when they've hit something:
+//! damage numbers float up from the victim, its sprite flashes,
+//! and sparks fly off in the direction it was hit.
+use super::CedCam2D;
+use crate::Game;
+use glam::vec3;
+use macroquad::*;
+
+const NUMBER_FRAMES: u16 = 45;
+const NUMBER_RISE: f32 = 0.004;
+const NUMBER_SIZE: f32 = 26.0;
+const FLASH_FRAMES: u16 = 8;
+const SPARK_FRAMES: u16 = 10;
+const SPARK_LENGTH: f32 = 0.18;
+const SPARK_SPREAD: f32 = 0.5;
+
+/// A Component which tints an Entity's sprite for as many frames as it has left.
+#[derive(Debug, Clone, Copy)]
+pub struct Flash {
+    frames: u16,
+}
+impl Flash {
+    pub fn tint(self) -> Color {
+        Color([255, 90, 90, 255])
+    }
+}
+
+struct DamageNumber {
+    pos: na::Vector2<f32>,
+    amount: usize,
+    age: u16,
+}
+
+struct Spark {
+    iso: na::Isometry2<f32>,
+    age: u16,
+}
+
+/// Effects that live outside of the ECS because they have nothing to do with gameplay.
+#[derive(Default)]
+pub struct Feedback {
+    numbers: Vec<DamageNumber>,
+    sparks: Vec<Spark>,
+    flashes: Vec<hecs::Entity>,
+}
+impl Feedback {
+    /// The emitter's translation is where the hit landed,
+    /// the sparks fly out along its rotated x axis.
+    pub fn hit(&mut self, victim: hecs::Entity, emitter: na::Isometry2<f32>, amount: usize) {
+        self.flashes.push(victim);
+        self.sparks.push(Spark {
+            iso: emitter,
+            age: 0,
+        });
+        if amount > 0 {
+            self.numbers.push(DamageNumber {
+                pos: emitter.translation.vector,
+                amount,
+                age: 0,
+            });
+        }
+    }
+
+    /// Draws everything with the player's inverse isometry as the camera's.
+    pub fn draw(&self, config: &super::Config, player_iso_inverse: na::Isometry2<f32>) {
+        for &Spark { iso, age } in &self.sparks {
+            set_camera(config.camera(player_iso_inverse * iso));
+
+            let t = age as f32 / SPARK_FRAMES as f32;
+            let alpha = ((1.0 - t) * 255.0) as u8;
+            for i in -1..=1 {
+                let angle = i as f32 * SPARK_SPREAD;
+                let (start, end) = (SPARK_LENGTH * t, SPARK_LENGTH * (0.4 + t));
+                draw_line(
+                    angle.cos() * start,
+                    angle.sin() * start,
+                    angle.cos() * end,
+                    angle.sin() * end,
+                    0.015,
+                    Color([255, 240, 170, alpha]),
+                );
+            }
+        }
+
+        let camera = config.camera(player_iso_inverse);
+        set_default_camera();
+        for &DamageNumber { pos, amount, age } in &self.numbers {
+            let t = age as f32 / NUMBER_FRAMES as f32;
+            let screen =
+                world_to_pixels(&camera, pos - na::Vector2::y() * NUMBER_RISE * age as f32);
+            draw_text(
+                &amount.to_string(),
+                screen.x(),
+                screen.y(),
+                NUMBER_SIZE,
+                Color([255, 255, 255, ((1.0 - t) * 255.0) as u8]),
+            );
+        }
+    }
+}
+
+fn world_to_pixels(camera: &CedCam2D, p: na::Vector2<f32>) -> Vec2 {
+    let clip = camera.matrix().transform_point3(vec3(p.x, p.y, 0.0));
+    vec2(
+        (clip.x() + 1.0) * screen_width() / 2.0,
+        (1.0 - clip.y()) * screen_height() / 2.0,
+    )
+}
+
+/// Flashes freshly hit Entities, ages effects and clears out the ones that have run their course.
+pub fn update(Game { ecs, feedback, .. }: &mut Game) {
+    for e in feedback.flashes.drain(..) {
+        drop(ecs.insert_one(
+            e,
+            Flash {
+                frames: FLASH_FRAMES,
+            },
+        ));
+    }
+
+    let finished: Vec<hecs::Entity> = ecs
+        .query::<&mut Flash>()
+        .iter()
+        .filter_map(|(e, flash)| {
+            flash.frames = flash.frames.saturating_sub(1);
+            Some(e).filter(|_| flash.frames == 0)
+        })
+        .collect();
+    for e in finished {
+        drop(ecs.remove_one::<Flash>(e));
+    }
+
+    feedback.numbers.retain(|n| n.age < NUMBER_FRAMES);
+    for n in &mut feedback.numbers {
+        n.age += 1;
+    }
+    feedback.sparks.retain(|s| s.age < SPARK_FRAMES);
+    for s in &mut feedback.sparks {
+        s.age += 1;
+    }
+}
@@ -1,14 +1,16 @@
 use crate::{
+    combat,
     phys::{self, PhysHandle},
-    Game,
+    world, Game,
 };
 use macroquad::{drawing::Texture2D, *};
-use ncollide2d::shape::Cuboid;
 use std::{fmt, num::NonZeroUsize};
 
 const ONE: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(1) };
 mod cam;
 pub use cam::CedCam2D;
+pub mod feedback;
+pub use feedback::{Feedback, Flash};
 
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -33,6 +35,16 @@ impl Config {
         )
     }
 
+    /// Like `art`, but doesn't panic, and will also accept the file name without
+    /// its extension, i.e. "baseball" will find "baseball.png".
+    pub fn find_art(&self, name: &str) -> Option<ArtHandle> {
+        self.art
+            .iter()
+            .position(|a| a.file == name)
+            .or_else(|| self.art.iter().position(|a| a.name() == name))
+            .map(ArtHandle)
+    }
+
     /// The mouse position in world coordinates
     pub fn mouse_world(&self) -> Vec2 {
         let screen = Vec2::new(screen_width(), screen_height());
@@ -222,9 +234,92 @@ impl AnimationFrame {
     }
 }
 
-pub fn animate(Game { ecs, .. }: &mut Game) {
-    for (_, AnimationFrame(af)) in ecs.query::<&mut AnimationFrame>().iter() {
-        *af += 1;
+/// Gives finer control over how an Entity's AnimationFrame advances.
+/// Entities without one simply loop through their spritesheet at its frame rate.
+#[derive(Clone, Copy, Debug)]
+pub struct Playback {
+    /// How many frames the AnimationFrame advances each tick; 1.0 is normal speed.
+    pub speed: f32,
+    /// If false, the animation stops on its last frame instead of starting over.
+    pub looping: bool,
+    pub paused: bool,
+    /// Fractional frames accumulated when the speed isn't a whole number.
+    progress: f32,
+}
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            looping: true,
+            paused: false,
+            progress: 0.0,
+        }
+    }
+}
+impl Playback {
+    /// How many whole frames to advance this tick.
+    fn advance(&mut self) -> usize {
+        if self.paused {
+            return 0;
+        }
+        self.progress += self.speed.max(0.0);
+        let steps = self.progress.floor();
+        self.progress -= steps;
+        steps as usize
+    }
+}
+
+/// Advances AnimationFrames, letting scripts know when frames change
+/// and when spritesheets finish playing.
+pub fn animate(Game { ecs, config, .. }: &mut Game) {
+    let mut scripts = glsp::lib_mut::<world::script::Cache>();
+
+    for (e, (af, looks, playback)) in ecs
+        .query::<(&mut AnimationFrame, Option<&Looks>, Option<&mut Playback>)>()
+        .iter()
+    {
+        let (steps, looping) = match playback {
+            Some(pb) => (pb.advance(), pb.looping),
+            None => (1, true),
+        };
+        if steps == 0 {
+            continue;
+        }
+
+        // without a spritesheet, there are no frames to tell anyone about
+        let art_ss = looks
+            .map(|l| config.draw.get(l.art))
+            .and_then(|art| Some((art, art.spritesheet?)));
+        let (art, ss) = match art_ss {
+            Some(art_ss) => art_ss,
+            None => {
+                af.0 += steps;
+                continue;
+            }
+        };
+        let before = af.current_frame(ss);
+        let last_tick = ss.total.get() * ss.frame_rate.get() - 1;
+
+        let finished = if looping {
+            af.0 += steps;
+            af.current_frame(ss) < before
+        } else if af.0 < last_tick {
+            af.0 = (af.0 + steps).min(last_tick);
+            af.0 == last_tick
+        } else {
+            false
+        };
+
+        let after = af.current_frame(ss);
+        if after != before {
+            scripts.frame_changes.push((e, after));
+        }
+        if finished {
+            match glsp::sym(art.name()) {
+                Ok(name) => scripts.anim_finished.push((e, name)),
+                Err(err) => glsp::eprn!("couldn't symmify {}: {}", art.file, err),
+            }
+        }
     }
 }
 
@@ -239,6 +334,11 @@ pub struct ArtConfig {
     pub align: Align,
 }
 impl ArtConfig {
+    /// The file name without its extension, i.e. "baseball" for "baseball.png".
+    pub fn name(&self) -> &str {
+        self.file.rsplitn(2, '.').last().unwrap_or(&self.file)
+    }
+
     #[cfg(feature = "confui")]
     fn dev_ui(&mut self, ui: &mut egui::Ui) -> ArtConfigDevUiRequest {
         ui.label("file name");
@@ -377,6 +477,50 @@ impl DeathAnimation {
     }
 }
 
+/// A Component that draws a bar above an Entity's sprite
+/// showing how much of its Health it has left.
+///
+/// The bar is hidden while the Entity is at full health.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthBar {
+    /// The amount of Health at which the bar is full.
+    pub max_health: usize,
+    /// How wide the bar is, in world units.
+    pub width: f32,
+}
+impl Default for HealthBar {
+    fn default() -> Self {
+        Self {
+            max_health: 1,
+            width: 0.4,
+        }
+    }
+}
+impl HealthBar {
+    /// How full the bar should be, or None if it shouldn't be shown.
+    fn fraction(self, hp: combat::Health) -> Option<f32> {
+        let points = hp.points()?;
+        Some(points as f32 / self.max_health.max(1) as f32).filter(|&f| f < 1.0)
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = *self;
+
+        ui.label("max health");
+        let mut max = self.max_health as f32;
+        ui.add(egui::DragValue::f32(&mut max));
+        self.max_health = max.max(1.0) as usize;
+
+        ui.label("width");
+        ui.add(egui::DragValue::f32(&mut self.width).speed(0.001));
+
+        *self != before
+    }
+}
+
 /// A Component that is active on Ghost entities as they animate a death.
 /// The bool should start out as false and be set to true if the animation has begun playing.
 pub struct AnimatingDeath(bool);
@@ -391,12 +535,11 @@ pub fn insert_ghosts(
         ..
     }: &mut Game,
 ) {
-    for (death_anim, iso, half_extents) in dead.marks().filter_map(
-        |e| -> Option<(DeathAnimation, na::Isometry2<f32>, na::Vector2<f32>)> {
+    for (death_anim, iso, shape) in dead.marks().filter_map(
+        |e| -> Option<(DeathAnimation, na::Isometry2<f32>, phys::ShapeHandle<f32>)> {
             let (&death_anim, &h) = ecs.query_one::<(&_, &_)>(e).ok()?.get()?;
             let obj = phys.collision_object(h)?;
-            let Cuboid { half_extents, .. } = obj.shape().as_shape()?;
-            Some((death_anim, *obj.position(), *half_extents))
+            Some((death_anim, *obj.position(), obj.shape().clone()))
         },
     ) {
         l8r.l8r(move |Game { ecs, phys, .. }| {
@@ -410,7 +553,7 @@ pub fn insert_ghosts(
                 phys,
                 ghost,
                 iso,
-                Cuboid::new(half_extents),
+                shape,
                 phys::CollisionGroups::new().with_whitelist(&[]),
             );
         });
@@ -446,6 +589,8 @@ type SpriteData = (
     na::Isometry2<f32>,
     na::Vector2<f32>,
     Option<AnimationFrame>,
+    Color,
+    Option<(HealthBar, f32)>,
 );
 
 pub fn draw(
@@ -457,6 +602,8 @@ pub fn draw(
         map,
         images,
         draw_state,
+        feedback,
+        stats,
         ..
     }: &mut Game,
 ) {
@@ -505,17 +652,29 @@ pub fn draw(
     }
 
     draw_state.sprites.extend(
-        ecs.query::<(&Looks, &PhysHandle, Option<&AnimationFrame>)>()
-            .iter()
-            .filter_map(|(_, (&l, &h, af))| {
-                let o = phys.collision_object(h)?;
-                let half_extents = o.shape().as_shape::<Cuboid<f32>>().unwrap().half_extents;
-                Some((l, *o.position(), half_extents, af.copied()))
-            }),
+        ecs.query::<(
+            &Looks,
+            &PhysHandle,
+            Option<&AnimationFrame>,
+            Option<&Flash>,
+            Option<&combat::StatusEffects>,
+            Option<(&HealthBar, &combat::Health)>,
+        )>()
+        .iter()
+        .filter_map(|(_, (&l, &h, af, flash, status, bar))| {
+            let o = phys.collision_object(h)?;
+            let half_extents = phys::half_extents(&**o.shape());
+            let tint = flash
+                .map(|f| f.tint())
+                .or_else(|| status?.tint())
+                .unwrap_or(WHITE);
+            let bar = bar.and_then(|(&bar, &hp)| Some((bar, bar.fraction(hp)?)));
+            Some((l, *o.position(), half_extents, af.copied(), tint, bar))
+        }),
     );
 
     draw_state.sprites.sort_unstable_by(|a, b| {
-        fn f((looks, iso_a, _, _): &SpriteData) -> f32 {
+        fn f((looks, iso_a, _, _, _, _): &SpriteData) -> f32 {
             iso_a.translation.vector.y + looks.z_offset
         }
 
@@ -523,7 +682,7 @@ pub fn draw(
             .unwrap_or(std::cmp::Ordering::Greater)
     });
 
-    for (looks, iso, half_size, anim_frame) in draw_state.sprites.drain(..) {
+    for (looks, iso, half_size, anim_frame, tint, bar) in draw_state.sprites.drain(..) {
         let camera = config
             .draw
             .camera_x_flipped(player_iso_inverse * iso, looks.flip_x);
@@ -538,14 +697,15 @@ pub fn draw(
             }
         };
         let world_size = size * looks.scale * art.scale;
+        let top = match art.align {
+            Align::Bottom => -world_size.y() + half_size.y - looks.bottom_offset,
+            Align::Center => world_size.y() / -2.0 - looks.bottom_offset,
+        };
         draw_texture_ex(
             *image,
             world_size.x() / -2.0,
-            match art.align {
-                Align::Bottom => -world_size.y() + half_size.y - looks.bottom_offset,
-                Align::Center => world_size.y() / -2.0 - looks.bottom_offset,
-            },
-            WHITE,
+            top,
+            tint,
             DrawTextureParams {
                 dest_size: Some(world_size),
                 source: art.spritesheet.and_then(|ss| {
@@ -559,9 +719,74 @@ pub fn draw(
                 }),
                 ..Default::default()
             },
-        )
+        );
+
+        // drawn right after the sprite so that it shares its place in the y-sort
+        if let Some((bar, fraction)) = bar {
+            const HEIGHT: f32 = 0.035;
+            const GAP: f32 = 0.05;
+
+            // the bar shouldn't spin or flip with the Entity
+            let upright = na::Isometry2::new(iso.translation.vector, 0.0);
+            set_camera(config.draw.camera(player_iso_inverse * upright));
+
+            let (x, y) = (bar.width / -2.0, top - GAP - HEIGHT);
+            draw_rectangle(x, y, bar.width, HEIGHT, Color([20, 20, 20, 200]));
+            draw_rectangle(
+                x,
+                y,
+                bar.width * fraction,
+                HEIGHT,
+                Color([220, 40, 40, 255]),
+            );
+        }
     }
 
+    // how charged up the golem's throw is, shown at its feet
+    if let Some((fraction, overcharged)) = player.charge(&config.player) {
+        const WIDTH: f32 = 0.5;
+        const HEIGHT: f32 = 0.035;
+        const GAP: f32 = 0.05;
+
+        if let Some(obj) = phys.collision_object(player.phys_handle) {
+            let half_extents = phys::half_extents(&**obj.shape());
+            let upright = na::Isometry2::new(obj.position().translation.vector, 0.0);
+            set_camera(config.draw.camera(player_iso_inverse * upright));
+
+            let fill = if overcharged {
+                Color([220, 40, 40, 255])
+            } else if fraction >= 1.0 {
+                Color([255, 240, 120, 255])
+            } else {
+                Color([240, 160, 40, 255])
+            };
+            let (x, y) = (WIDTH / -2.0, half_extents.y + GAP);
+            draw_rectangle(x, y, WIDTH, HEIGHT, Color([20, 20, 20, 200]));
+            draw_rectangle(x, y, WIDTH * fraction, HEIGHT, fill);
+        }
+    }
+
+    // where the golem's throw is predicted to go
+    if let Some(trajectory) = player
+        .trajectory
+        .as_ref()
+        .filter(|_| player.state.is_throwing())
+    {
+        const DOT_RADIUS: f32 = 0.015;
+        const STOP_RADIUS: f32 = 0.06;
+
+        set_camera(config.draw.camera(player_iso_inverse));
+        for p in trajectory.points.iter() {
+            draw_circle(p.x, p.y, DOT_RADIUS, Color([255, 255, 255, 150]));
+        }
+        if let Some(stop) = trajectory.stop {
+            let color = if stop.enemy { RED } else { WHITE };
+            draw_circle_lines(stop.pos.x, stop.pos.y, STOP_RADIUS, DOT_RADIUS, color);
+        }
+    }
+
+    feedback.draw(&config.draw, player_iso_inverse);
+
     #[cfg(feature = "confui")]
     if config.draw_debug {
         for obj in ecs
@@ -569,14 +794,48 @@ pub fn draw(
             .iter()
             .filter_map(|(_, &h)| phys.collision_object(h))
         {
-            let half = obj.shape().as_shape::<Cuboid<f32>>().unwrap().half_extents;
-            let size = half * 2.0;
-            let pos = -half;
+            let camera = config.draw.camera(player_iso_inverse * obj.position());
+            set_camera(camera);
+
+            draw_shape_lines(&**obj.shape(), 0.01, RED);
+        }
 
+        for obj in ecs
+            .query::<&phys::Trigger>()
+            .iter()
+            .filter_map(|(_, t)| phys.collision_object(t.handle))
+        {
             let camera = config.draw.camera(player_iso_inverse * obj.position());
             set_camera(camera);
 
-            draw_rectangle_lines(pos.x, pos.y, size.x, size.y, 0.01, RED);
+            draw_shape_lines(&**obj.shape(), 0.01, YELLOW);
+        }
+    }
+    // last, because it draws in screen space
+    stats.draw(player.state.is_dead() || is_key_down(KeyCode::Tab));
+}
+
+/// Outlines a collision shape, relative to the current camera.
+#[cfg(feature = "confui")]
+fn draw_shape_lines(s: &dyn ncollide2d::shape::Shape<f32>, thickness: f32, color: Color) {
+    use ncollide2d::shape;
+
+    if let Some(cuboid) = s.as_shape::<shape::Cuboid<f32>>() {
+        let half = cuboid.half_extents;
+        let size = half * 2.0;
+        draw_rectangle_lines(-half.x, -half.y, size.x, size.y, thickness, color);
+    } else if let Some(ball) = s.as_shape::<shape::Ball<f32>>() {
+        draw_circle_lines(0.0, 0.0, ball.radius(), thickness, color);
+    } else if let Some(capsule) = s.as_shape::<shape::Capsule<f32>>() {
+        let (h, r) = (capsule.half_height(), capsule.radius());
+        draw_circle_lines(0.0, -h, r, thickness, color);
+        draw_circle_lines(0.0, h, r, thickness, color);
+        draw_line(-r, -h, -r, h, thickness, color);
+        draw_line(r, -h, r, h, thickness, color);
+    } else if let Some(poly) = s.as_shape::<shape::ConvexPolygon<f32>>() {
+        let points = poly.points();
+        for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+            draw_line(a.x, a.y, b.x, b.y, thickness, color);
         }
     }
 }
@@ -9,6 +9,71 @@ use hecs::Entity;
 #[derive(serde::Deserialize, serde::Serialize, Default, Clone, PartialEq, Debug)]
 pub struct CollisionStatic;
 
+/// How hard an Entity is to push out of the things it runs into, and how quickly
+/// those pushes die down. Entities without a Mass behave as though they had the default one.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Mass {
+    /// Only meaningful relative to other Masses; two things of equal mass split a collision
+    /// evenly, while a thing twice as heavy as another is pushed half as far.
+    pub mass: f32,
+    /// Domain [0, 1]; how much of the push out of other things carries over into the next frame.
+    pub damping: f32,
+    /// Domain [0, 1]; how much of any Force or Velocity carrying this Entity into something
+    /// bounces back. At 0 it just stops going that way, at 1 it bounces off at full speed.
+    pub restitution: f32,
+}
+impl Default for Mass {
+    fn default() -> Self {
+        Self {
+            mass: 1.0,
+            damping: 0.87,
+            restitution: 0.0,
+        }
+    }
+}
+impl Mass {
+    fn inverse(&self) -> f32 {
+        1.0 / self.mass.max(std::f32::EPSILON)
+    }
+
+    /// How much of the push out of `other` this Mass takes, where 1.0 is the whole push.
+    /// Things that collide with CollisionStatic Entities, passed in as None,
+    /// take the whole push; otherwise, the two shares add up to the whole push.
+    fn share(&self, other: Option<&Mass>) -> f32 {
+        match other {
+            None => 1.0,
+            Some(other) => self.inverse() / (self.inverse() + other.inverse()),
+        }
+    }
+
+    /// Takes away the part of `vec` going into `normal`, and bounces some of it back.
+    fn bounce(&self, vec: &mut na::Vector2<f32>, normal: na::Vector2<f32>) {
+        let into = vec.dot(&normal);
+        if into < 0.0 {
+            *vec -= normal * into * (1.0 + self.restitution);
+        }
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = *self;
+
+        ui.label("mass");
+        ui.add(egui::DragValue::f32(&mut self.mass).speed(0.01));
+        self.mass = self.mass.max(0.01);
+        ui.label("damping");
+        ui.add(egui::DragValue::f32(&mut self.damping).speed(0.001));
+        self.damping = self.damping.max(0.0).min(1.0);
+        ui.label("restitution");
+        ui.add(egui::DragValue::f32(&mut self.restitution).speed(0.001));
+        self.restitution = self.restitution.max(0.0).min(1.0);
+
+        *self != before
+    }
+}
+
 /// Assigning this component to an Entity allows you to get finer grained control
 /// over what an Entity can collide with and be forced out of. The CollisionGroups
 /// you pass to `.add_hitbox` control all possible collisions your shape can collide with.
@@ -62,8 +127,10 @@ pub fn collision(world: &mut Game) {
     let ecs = &mut world.ecs;
     let phys = &mut world.phys;
 
+    super::trigger::follow(ecs, phys);
     phys.clear_events();
     phys.update();
+    super::trigger::proximity(ecs, phys, &mut scripts);
 
     phys.contact_events().into_iter().for_each(|event| {
         use ncollide2d::pipeline::ContactEvent;
@@ -112,16 +179,30 @@ pub fn collision(world: &mut Game) {
         (
             Contacts {
                 inner: contacts,
-                force,
+                force: push,
             },
             &collided_h,
             rigid_groups,
+            mass,
+            mut force,
+            mut velocity,
         ),
     ) in ecs
-        .query::<(&mut _, &_, Option<&RigidGroups>)>()
+        .query::<(
+            &mut _,
+            &_,
+            Option<&RigidGroups>,
+            Option<&Mass>,
+            Option<&mut super::Force>,
+            Option<&mut super::Velocity>,
+        )>()
         .without::<CollisionStatic>()
         .iter()
     {
+        // only Entities that were given a Mass bounce off of things
+        let bounces = mass.is_some();
+        let mass = mass.copied().unwrap_or_default();
+
         for &other_ent in contacts.iter() {
             // if the recorded contact is with an entity that can't be found,
             // just ignore it, they've probably been deleted or something.
@@ -136,11 +217,27 @@ pub fn collision(world: &mut Game) {
 
                 if let Some((l, _, _, contacts)) = phys.contact_pair(collided_h, other_h, true) {
                     let deepest = contacts.deepest_contact().unwrap().contact;
-                    let mut normal = deepest.normal.into_inner() * deepest.depth;
+                    let mut normal = deepest.normal.into_inner();
                     if l == collided_h {
                         normal *= -1.0
                     }
-                    *force += normal;
+
+                    let share = if ecs.get::<CollisionStatic>(other_ent).is_ok() {
+                        mass.share(None)
+                    } else {
+                        let other_mass = ecs.get::<Mass>(other_ent).ok().as_deref().copied();
+                        mass.share(Some(&other_mass.unwrap_or_default()))
+                    };
+                    *push += normal * deepest.depth * share;
+
+                    if bounces {
+                        if let Some(force) = &mut force {
+                            mass.bounce(&mut force.vec, normal);
+                        }
+                        if let Some(velocity) = &mut velocity {
+                            mass.bounce(&mut velocity.vec, normal);
+                        }
+                    }
                 }
             }
         }
@@ -152,15 +249,18 @@ pub fn collision(world: &mut Game) {
             )
         });
 
-        *force *= 0.87;
+        *push *= mass.damping;
 
         let mut iso = obj.position().clone();
-        iso.translation.vector += *force;
-        obj.set_position(iso);
+        iso.translation.vector += *push;
+        obj.set_position_with_prediction(iso.clone(), {
+            iso.translation.vector += *push * mass.damping;
+            iso
+        });
     }
 }
 
-/// Remove the Collision Objects of dead Entities from the CollisionWorld
+/// Remove the Collision Objects of dead Entities, and of their Triggers, from the CollisionWorld
 pub fn clear_dead_collision_objects(world: &mut Game) {
     let ecs = &world.ecs;
     let phys = &mut world.phys;
@@ -169,8 +269,28 @@ pub fn clear_dead_collision_objects(world: &mut Game) {
         world
             .dead
             .marks()
-            .filter_map(|e| ecs.get::<PhysHandle>(e).ok().as_deref().copied())
+            .flat_map(|e| {
+                let body = ecs.get::<PhysHandle>(e).ok().as_deref().copied();
+                let trigger = ecs.get::<super::Trigger>(e).ok().map(|t| t.handle);
+                body.into_iter().chain(trigger)
+            })
             .collect::<Vec<PhysHandle>>()
             .as_slice(),
     );
 }
+
+#[test]
+fn mass_shares() {
+    let light = Mass {
+        mass: 1.0,
+        ..Default::default()
+    };
+    let heavy = Mass {
+        mass: 4.0,
+        ..Default::default()
+    };
+    assert_eq!(light.share(Some(&light)), 0.5);
+    assert_eq!(light.share(None), 1.0);
+    assert_eq!(light.share(Some(&heavy)), 0.8);
+    assert_eq!(heavy.share(Some(&light)), 0.2);
+}
@@ -0,0 +1,269 @@
+use super::CollisionGroups;
+use glsp::{FromVal, Lib};
+
+/// ncollide can keep track of 30 groups, the last of which is kept for Triggers.
+pub const MAX_GROUPS: usize = 29;
+/// Triggers, and only Triggers, belong to this group.
+const TRIGGER_GROUP: usize = MAX_GROUPS;
+
+/// A collision group, referred to by the name it's declared with in the CollideConfig.
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(transparent)]
+pub struct Collide(pub String);
+impl Collide {
+    pub fn new(name: &str) -> Self {
+        Collide(name.to_string())
+    }
+
+    /// Which of ncollide's groups this is, if a group by this name has been declared.
+    pub fn index(&self) -> Option<usize> {
+        CollideTable::borrow().index(&self.0)
+    }
+}
+impl FromVal for Collide {
+    fn from_val(val: &glsp::Val) -> glsp::GResult<Self> {
+        let sym = glsp::Sym::from_val(val)?;
+        let collide = Collide(sym.name().to_string());
+        if collide.index().is_none() {
+            glsp::bail!("Not a valid Collision marker: {}", sym);
+        }
+        Ok(collide)
+    }
+}
+
+/// The collision groups there are, and which of them never have anything to do with each other.
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CollideConfig {
+    /// Each group's position in this list is the ncollide group it's given,
+    /// so only the first `MAX_GROUPS` of them are used.
+    pub groups: Vec<String>,
+    /// Pairs of groups that never interact, no matter what anything's whitelist says.
+    #[serde(default)]
+    pub ignored: Vec<(String, String)>,
+
+    #[cfg(feature = "confui")]
+    #[serde(skip)]
+    adding: String,
+}
+impl Default for CollideConfig {
+    fn default() -> Self {
+        Self {
+            groups: ["Player", "Weapon", "Enemy", "World", "Creature"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            ignored: vec![],
+            #[cfg(feature = "confui")]
+            adding: String::new(),
+        }
+    }
+}
+impl CollideConfig {
+    fn ignores(&self, a: &str, b: &str) -> bool {
+        self.ignored
+            .iter()
+            .any(|(x, y)| (x == a && y == b) || (x == b && y == a))
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.label("Changes only affect things spawned after they're made.");
+
+        ui.collapsing("Groups", |ui| {
+            let mut removal_index: Option<usize> = None;
+            for (i, name) in self.groups.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}: {}", i, name));
+                    if ui.button("Remove").clicked {
+                        removal_index = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removal_index {
+                let name = self.groups.remove(i);
+                self.ignored.retain(|(a, b)| *a != name && *b != name);
+            }
+
+            if self.groups.len() < MAX_GROUPS {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::new(&mut self.adding));
+                    let name = self.adding.trim();
+                    if ui.button("Add").clicked
+                        && !name.is_empty()
+                        && !self.groups.iter().any(|g| g == name)
+                    {
+                        self.groups.push(name.to_string());
+                        self.adding.clear();
+                    }
+                });
+            } else {
+                ui.label(format!("Only {} groups can be declared", MAX_GROUPS));
+            }
+        });
+
+        ui.collapsing("Interactions", |ui| {
+            for a in self.groups.clone().iter() {
+                ui.collapsing(a, |ui| {
+                    for b in self.groups.clone().iter() {
+                        let mut interacts = !self.ignores(a, b);
+                        ui.checkbox(b, &mut interacts);
+                        if interacts == self.ignores(a, b) {
+                            if interacts {
+                                self.ignored
+                                    .retain(|(x, y)| !((x == a && y == b) || (x == b && y == a)));
+                            } else {
+                                self.ignored.push((a.clone(), b.clone()));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        *self != before
+    }
+}
+
+glsp::lib! {
+    /// The CollideConfig, digested so that groups can be quickly looked up by name.
+    /// This is kept apart from the Game so it can be used while the Game is borrowed.
+    pub struct CollideTable {
+        #[cfg(feature = "confui")]
+        names: Vec<String>,
+        indices: fxhash::FxHashMap<String, usize>,
+        /// Bit `j` of `ignores[i]` is set if group `i` never interacts with group `j`.
+        ignores: [u32; MAX_GROUPS],
+    }
+}
+impl CollideTable {
+    pub fn new(config: &CollideConfig) -> Self {
+        if config.groups.len() > MAX_GROUPS {
+            glsp::eprn!(
+                "only {} collision groups are supported, ignoring {}",
+                MAX_GROUPS,
+                config.groups[MAX_GROUPS..].join(", ")
+            );
+        }
+        let groups = &config.groups[..config.groups.len().min(MAX_GROUPS)];
+
+        let mut ignores = [0; MAX_GROUPS];
+        for (i, a) in groups.iter().enumerate() {
+            for (j, b) in groups.iter().enumerate() {
+                if config.ignores(a, b) {
+                    ignores[i] |= 1 << j;
+                }
+            }
+        }
+
+        Self {
+            #[cfg(feature = "confui")]
+            names: groups.to_vec(),
+            indices: groups
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.clone(), i))
+                .collect(),
+            ignores,
+        }
+    }
+
+    #[cfg(feature = "confui")]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// The indices of the named groups, leaving out any that haven't been declared.
+    pub fn indices<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+        names.into_iter().filter_map(|n| self.index(n)).collect()
+    }
+
+    /// Lets these CollisionGroups set off Triggers,
+    /// and blacklists any group that every one of their groups ignores.
+    pub fn finish(&self, mut groups: CollisionGroups) -> CollisionGroups {
+        groups.modify_whitelist(TRIGGER_GROUP, true);
+
+        let ignored_by_all = (0..MAX_GROUPS)
+            .filter(|&i| groups.is_member_of(i))
+            .fold(None, |acc: Option<u32>, i| {
+                Some(acc.map_or(self.ignores[i], |acc| acc & self.ignores[i]))
+            })
+            .unwrap_or(0);
+
+        for j in (0..MAX_GROUPS).filter(|j| ignored_by_all & (1 << j) != 0) {
+            groups.modify_blacklist(j, true);
+        }
+        groups
+    }
+}
+
+/// CollisionGroups that belong to, whitelist and blacklist the named groups, in that order,
+/// and that respect the CollideConfig's ignored pairs.
+/// `None` leaves ncollide's default in place, i.e. every group for membership and whitelist.
+pub fn collision_groups(
+    membership: Option<&[&str]>,
+    whitelist: Option<&[&str]>,
+    blacklist: &[&str],
+) -> CollisionGroups {
+    let table = CollideTable::borrow();
+    let mut groups =
+        CollisionGroups::new().with_blacklist(&table.indices(blacklist.iter().copied()));
+    if let Some(membership) = membership {
+        groups = groups.with_membership(&table.indices(membership.iter().copied()));
+    }
+    if let Some(whitelist) = whitelist {
+        groups = groups.with_whitelist(&table.indices(whitelist.iter().copied()));
+    }
+    table.finish(groups)
+}
+
+/// CollisionGroups for a Trigger that detects the named groups.
+/// Triggers never detect each other.
+pub fn trigger_groups(detects: &[&str]) -> CollisionGroups {
+    let table = CollideTable::borrow();
+    table.finish(
+        CollisionGroups::new()
+            .with_membership(&[TRIGGER_GROUP])
+            .with_whitelist(&table.indices(detects.iter().copied()))
+            .with_blacklist(&[TRIGGER_GROUP]),
+    )
+}
+
+#[test]
+fn ignored_pairs() {
+    let config = CollideConfig {
+        groups: vec![
+            "Player".to_string(),
+            "Enemy".to_string(),
+            "World".to_string(),
+        ],
+        ignored: vec![("Enemy".to_string(), "World".to_string())],
+        ..Default::default()
+    };
+    let table = CollideTable::new(&config);
+    assert_eq!(table.index("World"), Some(2));
+    assert_eq!(table.indices(vec!["Enemy", "Nope", "Player"]), vec![1, 0]);
+
+    let enemy = table.finish(CollisionGroups::new().with_membership(&[1]));
+    let world = CollisionGroups::new().with_membership(&[2]);
+    let player = CollisionGroups::new().with_membership(&[0]);
+    assert!(!enemy.can_interact_with_groups(&world));
+    assert!(enemy.can_interact_with_groups(&player));
+
+    let trigger = table.finish(
+        CollisionGroups::new()
+            .with_membership(&[TRIGGER_GROUP])
+            .with_whitelist(&[1])
+            .with_blacklist(&[TRIGGER_GROUP]),
+    );
+    assert!(trigger.can_interact_with_groups(&enemy));
+    assert!(!trigger.can_interact_with_groups(&trigger));
+    assert!(!enemy.is_member_of(TRIGGER_GROUP) && !trigger.is_member_of(1));
+}
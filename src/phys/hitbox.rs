@@ -0,0 +1,167 @@
+use ncollide2d::shape::{self, Shape, ShapeHandle};
+
+/// The shape an Entity collides with, centered on its position.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub enum Hitbox {
+    /// Width and height.
+    Cuboid(na::Vector2<f32>),
+    /// Radius.
+    Circle(f32),
+    /// A rectangle standing upright with a half circle capping each end.
+    Capsule { half_height: f32, radius: f32 },
+    /// Corners of a convex polygon, relative to its center.
+    /// If the corners don't make a convex shape, their convex hull is used.
+    Convex(Vec<na::Vector2<f32>>),
+}
+impl Hitbox {
+    /// None if this is a polygon with fewer than three corners that aren't in a line.
+    pub fn shape(&self) -> Option<ShapeHandle<f32>> {
+        Some(match self {
+            &Hitbox::Cuboid(size) => ShapeHandle::new(shape::Cuboid::new(size / 2.0)),
+            &Hitbox::Circle(radius) => ShapeHandle::new(shape::Ball::new(radius)),
+            &Hitbox::Capsule {
+                half_height,
+                radius,
+            } => ShapeHandle::new(shape::Capsule::new(half_height, radius)),
+            Hitbox::Convex(corners) if corners.len() < 3 => return None,
+            Hitbox::Convex(corners) => {
+                let points: Vec<na::Point2<f32>> =
+                    corners.iter().map(|&c| na::Point2::from(c)).collect();
+                ShapeHandle::new(shape::ConvexPolygon::try_from_points(&points)?)
+            }
+        })
+    }
+
+    /// Half of the width and height of the smallest rectangle centered on the Entity
+    /// that fits around this shape. For lopsided polygons, that's a bit bigger than
+    /// the smallest rectangle that fits around the polygon itself.
+    #[cfg(feature = "confui")]
+    pub fn half_extents(&self) -> na::Vector2<f32> {
+        match self {
+            &Hitbox::Cuboid(size) => size / 2.0,
+            &Hitbox::Circle(radius) => na::Vector2::repeat(radius),
+            &Hitbox::Capsule {
+                half_height,
+                radius,
+            } => na::Vector2::new(radius, half_height + radius),
+            Hitbox::Convex(corners) => corners
+                .iter()
+                .fold(na::zero(), |he: na::Vector2<f32>, c| he.sup(&c.abs())),
+        }
+    }
+
+    #[cfg(feature = "confui")]
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        let he = self.half_extents();
+        let defaults = [
+            Hitbox::Cuboid(he * 2.0),
+            Hitbox::Circle(he.max()),
+            Hitbox::Capsule {
+                half_height: (he.y - he.x).max(0.0),
+                radius: he.x,
+            },
+            Hitbox::Convex(vec![
+                na::Vector2::new(-he.x, he.y),
+                na::Vector2::new(he.x, he.y),
+                na::Vector2::new(0.0, -he.y),
+            ]),
+        ];
+        ui.horizontal(|ui| {
+            for d in defaults.iter() {
+                let same_kind = std::mem::discriminant(d) == std::mem::discriminant(self);
+                if ui.radio(d.name(), same_kind).clicked && !same_kind {
+                    *self = d.clone();
+                }
+            }
+        });
+
+        match self {
+            Hitbox::Cuboid(size) => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::f32(&mut size.x).speed(0.001));
+                    ui.add(egui::DragValue::f32(&mut size.y).speed(0.001));
+                });
+            }
+            Hitbox::Circle(radius) => {
+                ui.label("radius");
+                ui.add(egui::DragValue::f32(radius).speed(0.001));
+            }
+            Hitbox::Capsule {
+                half_height,
+                radius,
+            } => {
+                ui.label("half height");
+                ui.add(egui::DragValue::f32(half_height).speed(0.001));
+                ui.label("radius");
+                ui.add(egui::DragValue::f32(radius).speed(0.001));
+            }
+            Hitbox::Convex(corners) => {
+                let mut removal_index: Option<usize> = None;
+                for (i, corner) in corners.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::f32(&mut corner.x).speed(0.001));
+                        ui.add(egui::DragValue::f32(&mut corner.y).speed(0.001));
+                        if ui.button("Remove").clicked {
+                            removal_index = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = removal_index {
+                    corners.remove(i);
+                }
+                if ui.button("Add Corner").clicked {
+                    corners.push(na::zero());
+                }
+            }
+        }
+        if self.shape().is_none() {
+            ui.label("Needs at least three corners that aren't in a line!");
+        }
+
+        *self != before
+    }
+
+    #[cfg(feature = "confui")]
+    fn name(&self) -> &'static str {
+        match self {
+            Hitbox::Cuboid(_) => "Cuboid",
+            Hitbox::Circle(_) => "Circle",
+            Hitbox::Capsule { .. } => "Capsule",
+            Hitbox::Convex(_) => "Convex",
+        }
+    }
+}
+
+/// Half of the width and height of the smallest rectangle
+/// that fits around this shape when it isn't rotated.
+pub fn half_extents(shape: &dyn Shape<f32>) -> na::Vector2<f32> {
+    shape.local_aabb().half_extents()
+}
+
+#[test]
+fn hitbox_shapes() {
+    let capsule = Hitbox::Capsule {
+        half_height: 0.5,
+        radius: 0.25,
+    };
+    let shape = capsule.shape().unwrap();
+    assert_eq!(half_extents(&*shape), na::Vector2::new(0.25, 0.75));
+
+    let triangle = Hitbox::Convex(vec![
+        na::Vector2::new(-1.0, 0.5),
+        na::Vector2::new(2.0, 0.5),
+        na::Vector2::new(0.0, -1.5),
+    ]);
+    let shape = triangle.shape().unwrap();
+    assert_eq!(half_extents(&*shape), na::Vector2::new(1.5, 1.0));
+    #[cfg(feature = "confui")]
+    assert_eq!(triangle.half_extents(), na::Vector2::new(2.0, 1.5));
+
+    let line = Hitbox::Convex(vec![
+        na::Vector2::new(-1.0, 0.0),
+        na::Vector2::new(1.0, 0.0),
+    ]);
+    assert!(line.shape().is_none());
+}
@@ -1,43 +1,20 @@
 pub mod collision;
+mod hitbox;
+pub use hitbox::{half_extents, Hitbox};
+mod trigger;
+pub use trigger::{trigger_insert, Trigger, TriggerConfig};
+mod groups;
+pub use groups::{collision_groups, trigger_groups, Collide, CollideConfig, CollideTable};
 
 pub type CollisionWorld = ncollide2d::world::CollisionWorld<f32, hecs::Entity>;
 pub type PhysHandle = ncollide2d::pipeline::CollisionObjectSlabHandle;
-pub use ncollide2d::{pipeline::CollisionGroups, shape::Cuboid};
-
-use crate::Game;
-use glsp::FromVal;
-
-/// Collision Group Constants
-#[derive(serde::Deserialize, serde::Serialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
-#[repr(u32)]
-pub enum Collide {
-    Player,
-    Weapon,
-    Enemy,
-    /// Fences, Terrain, etc.
-    World,
-    Creature,
-}
-impl FromVal for Collide {
-    fn from_val(val: &glsp::Val) -> glsp::GResult<Self> {
-        let sym = glsp::Sym::from_val(val)?;
-        Ok(match &*sym.name() {
-            "Player" => Self::Player,
-            "Weapon" => Self::Weapon,
-            "Enemy" => Self::Enemy,
-            "World" => Self::World,
-            "Creature" => Self::Creature,
-            _ => glsp::bail!("Not a valid Collision marker: {}", sym),
-        })
-    }
-}
-
-#[cfg(feature = "confui")]
-const ALL_COLLIDE: &[Collide] = {
-    use Collide::*;
-    &[Player, Weapon, Enemy, World, Creature]
+pub use ncollide2d::{
+    pipeline::CollisionGroups,
+    shape::{Cuboid, ShapeHandle},
 };
 
+use crate::{combat, Game};
+
 /// A collision relationship :P
 #[derive(serde::Deserialize, serde::Serialize, Default, Clone, PartialEq, Debug)]
 #[serde(deny_unknown_fields)]
@@ -90,8 +67,8 @@ impl Collisionship {
         ) {
             ui.collapsing(title, |ui| {
                 *adding = adding.take().and_then(|mut to_add| {
-                    for collide in ALL_COLLIDE.iter() {
-                        ui.radio_value(format!("{:?}", collide), &mut to_add, *collide);
+                    for name in glsp::lib::<CollideTable>().names() {
+                        ui.radio_value(name, &mut to_add, Collide::new(name));
                     }
                     if ui.button("Add").clicked {
                         *dirty = true;
@@ -107,14 +84,17 @@ impl Collisionship {
                     let mut to_remove: Option<Collide> = None;
                     for collide in list.iter() {
                         ui.horizontal(|ui| {
-                            ui.label(format!("{:?}", collide));
+                            ui.label(&collide.0);
                             if ui.button("Remove").clicked {
                                 to_remove = Some(collide.clone());
                             }
                         });
                     }
                     if ui.button("Add").clicked {
-                        *adding = Some(Collide::World);
+                        *adding = glsp::lib::<CollideTable>()
+                            .names()
+                            .first()
+                            .map(|n| Collide::new(n));
                     }
                     if let Some(c) = to_remove {
                         *dirty = true;
@@ -161,15 +141,16 @@ impl Into<(Option<collision::CollisionStatic>, CollisionGroups)> for Collisionsh
             membership,
             ..
         } = self;
-        let m = |l: std::collections::HashSet<Collide>| {
-            l.into_iter().map(|c| c as usize).collect::<Vec<_>>()
-        };
+        let table = glsp::lib::<CollideTable>();
+        let m = |l: std::collections::HashSet<Collide>| table.indices(l.iter().map(|c| &*c.0));
         (
             self.collision_static,
-            CollisionGroups::new()
-                .with_membership(&m(membership))
-                .with_whitelist(&m(whitelist))
-                .with_blacklist(&m(blacklist)),
+            table.finish(
+                CollisionGroups::new()
+                    .with_membership(&m(membership))
+                    .with_whitelist(&m(whitelist))
+                    .with_blacklist(&m(blacklist)),
+            ),
         )
     }
 }
@@ -178,12 +159,12 @@ pub fn phys_components(
     phys: &mut CollisionWorld,
     entity: hecs::Entity,
     iso: na::Isometry2<f32>,
-    cuboid: Cuboid<f32>,
+    shape: ShapeHandle<f32>,
     groups: CollisionGroups,
 ) -> (PhysHandle, collision::Contacts) {
     let (h, _) = phys.add(
         iso,
-        ncollide2d::shape::ShapeHandle::new(cuboid),
+        shape,
         groups,
         ncollide2d::pipeline::GeometricQueryType::Contacts(0.0, 0.0),
         entity,
@@ -196,10 +177,10 @@ pub fn phys_insert(
     phys: &mut CollisionWorld,
     entity: hecs::Entity,
     iso: na::Isometry2<f32>,
-    cuboid: Cuboid<f32>,
+    shape: ShapeHandle<f32>,
     groups: CollisionGroups,
 ) -> PhysHandle {
-    let comps = phys_components(phys, entity, iso, cuboid, groups);
+    let comps = phys_components(phys, entity, iso, shape, groups);
     let h = comps.0;
     ecs.insert(entity, comps).unwrap_or_else(|e| {
         panic!(
@@ -315,6 +296,71 @@ pub struct KnockBack {
     pub minimum_speed: Option<f32>,
 }
 
+/// How a KnockBack is described in a prefab's Comps.
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KnockBackConfig {
+    /// Only things these groups can interact with are knocked back.
+    pub groups: Collisionship,
+    pub force_decay: f32,
+    pub force_magnitude: f32,
+    #[serde(default)]
+    pub use_force_direction: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum_speed: Option<f32>,
+}
+impl Default for KnockBackConfig {
+    fn default() -> Self {
+        Self {
+            groups: Default::default(),
+            force_decay: 0.75,
+            force_magnitude: 0.5,
+            use_force_direction: false,
+            minimum_speed: None,
+        }
+    }
+}
+impl KnockBackConfig {
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.collapsing("groups", |ui| self.groups.dev_ui(ui));
+
+        ui.label("force magnitude");
+        ui.add(egui::DragValue::f32(&mut self.force_magnitude).speed(0.01));
+
+        ui.add(egui::Slider::f32(&mut self.force_decay, 0.0..=1.0).text("force decay"));
+
+        ui.checkbox("use force direction", &mut self.use_force_direction)
+            .tooltip_text("knock things back the way this is moving, not away from its center");
+
+        let mut has_min = self.minimum_speed.is_some();
+        ui.checkbox("minimum speed", &mut has_min);
+        self.minimum_speed = if has_min {
+            let mut ms = self.minimum_speed.unwrap_or(0.05);
+            ui.add(egui::DragValue::f32(&mut ms).speed(0.001));
+            Some(ms)
+        } else {
+            None
+        };
+
+        *self != before
+    }
+}
+impl Into<KnockBack> for KnockBackConfig {
+    fn into(self) -> KnockBack {
+        KnockBack {
+            groups: self.groups.into_groups(),
+            force_decay: self.force_decay,
+            force_magnitude: self.force_magnitude,
+            use_force_direction: self.use_force_direction,
+            minimum_speed: self.minimum_speed,
+        }
+    }
+}
+
 /// A Force is applied to an Entity every frame and decays a bit,
 /// eventually reaching 0 and being removed. Unlike a Velocity, a Force
 /// is only temporary, eventually fading away.
@@ -392,7 +438,123 @@ fn drag_goal(
     })
 }
 
-pub struct Velocity(na::Vector2<f32>);
+/// A Velocity moves an Entity every frame, and unlike a Force, it never goes away on its own.
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Velocity {
+    pub vec: na::Vector2<f32>,
+    /// Domain [0, 1]; the Velocity is multiplied by this every frame, so 1.0 never slows down.
+    pub damping: f32,
+    /// If set, the Velocity is never allowed to get any faster than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_speed: Option<f32>,
+}
+impl Default for Velocity {
+    fn default() -> Self {
+        Self::new(na::zero())
+    }
+}
+impl Velocity {
+    /// A Velocity that neither slows down nor has a maximum speed.
+    pub fn new(vec: na::Vector2<f32>) -> Self {
+        Self {
+            vec,
+            damping: 1.0,
+            max_speed: None,
+        }
+    }
+
+    /// Where `vec` would be if it got any faster than `max_speed`.
+    fn capped(&self, vec: na::Vector2<f32>) -> na::Vector2<f32> {
+        match self.max_speed {
+            Some(max) if vec.magnitude_squared() > max * max => vec.normalize() * max,
+            _ => vec,
+        }
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.label("velocity");
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::f32(&mut self.vec.x).speed(0.001));
+            ui.add(egui::DragValue::f32(&mut self.vec.y).speed(0.001));
+        });
+
+        ui.label("damping");
+        ui.add(egui::DragValue::f32(&mut self.damping).speed(0.001));
+        self.damping = self.damping.max(0.0).min(1.0);
+
+        let mut capped = self.max_speed.is_some();
+        ui.checkbox("max speed", &mut capped);
+        match (capped, &mut self.max_speed) {
+            (true, Some(max)) => {
+                ui.add(egui::DragValue::f32(max).speed(0.001));
+                *max = max.max(0.0);
+            }
+            (true, None) => self.max_speed = Some(self.vec.magnitude()),
+            (false, _) => self.max_speed = None,
+        }
+
+        *self != before
+    }
+}
+
+/// Marks Entities whose Forces might carry them clean through thin things in a single frame.
+/// Instead of jumping straight to where their Force would take them, they're swept along the way,
+/// stopping at and briefly touching the first thing they'd run into.
+#[derive(Default)]
+pub struct Bullet {
+    /// Things run into last frame, only kept in Contacts for that frame unless actually touching.
+    impacts: Vec<hecs::Entity>,
+    /// Set after running into something, so that the Force can be stopped next frame;
+    /// it's kept around until then so that the impact can hurt and knock back what was hit.
+    stopped: bool,
+}
+
+/// The first thing the collision object `h` would run into if it moved by `movement`,
+/// and how far it would get before it did.
+fn sweep(
+    phys: &CollisionWorld,
+    h: PhysHandle,
+    movement: na::Vector2<f32>,
+) -> Option<(hecs::Entity, f32)> {
+    use ncollide2d::pipeline::GeometricQueryType;
+
+    let obj = phys.collision_object(h)?;
+    let distance = movement.magnitude();
+    if distance == 0.0 {
+        return None;
+    }
+    let direction = na::Unit::new_unchecked(movement / distance);
+
+    let (other_h, toi) = phys
+        .sweep_test(
+            &**obj.shape(),
+            obj.position(),
+            &direction,
+            distance,
+            obj.collision_groups(),
+        )
+        // things already touching it will be taken care of by the usual contacts,
+        // and Triggers don't stop anything.
+        .filter(|(other_h, toi)| {
+            *other_h != h
+                && toi.toi > 0.0
+                && phys.collision_object(*other_h).map_or(false, |o| {
+                    matches!(o.query_type(), GeometricQueryType::Contacts(..))
+                })
+        })
+        .min_by(|(_, a), (_, b)| {
+            a.toi
+                .partial_cmp(&b.toi)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+
+    Some((*phys.collision_object(other_h)?.data(), toi.toi))
+}
 
 /// Also applies Forces and KnockBack.
 pub fn velocity(world: &mut Game) {
@@ -400,13 +562,22 @@ pub fn velocity(world: &mut Game) {
     let l8r = &mut world.l8r;
     let phys = &mut world.phys;
 
-    for (_, (h, &Velocity(vel))) in &mut world.ecs.query::<(&PhysHandle, &Velocity)>() {
+    for (vel_ent, (&h, vel)) in &mut world.ecs.query::<(&PhysHandle, &mut Velocity)>() {
         (|| {
-            let obj = phys.get_mut(*h)?;
+            let slow = ecs
+                .get::<combat::StatusEffects>(vel_ent)
+                .map_or(1.0, |fx| fx.slow_factor());
+
+            vel.vec = vel.capped(vel.vec);
+
+            let obj = phys.get_mut(h)?;
             let mut iso = obj.position().clone();
-            iso.translation.vector += vel;
+            iso.translation.vector += vel.vec * slow;
+
+            vel.vec *= vel.damping;
+
             obj.set_position_with_prediction(iso.clone(), {
-                iso.translation.vector += vel;
+                iso.translation.vector += vel.vec * slow;
                 iso
             });
 
@@ -473,17 +644,54 @@ pub fn velocity(world: &mut Game) {
         }
     }
 
-    for (force_ent, (&h, force)) in &mut world.ecs.query::<(&PhysHandle, &mut Force)>() {
+    for (force_ent, (&h, force, mut bullet, mut contacts)) in &mut world.ecs.query::<(
+        &PhysHandle,
+        &mut Force,
+        Option<&mut Bullet>,
+        Option<&mut collision::Contacts>,
+    )>() {
         (|| {
+            let slow = ecs
+                .get::<combat::StatusEffects>(force_ent)
+                .map_or(1.0, |fx| fx.slow_factor());
+
+            let mut movement = force.vec * slow;
+            if let Some(bullet) = bullet.as_mut() {
+                for other in bullet.impacts.drain(..) {
+                    let touching = ecs
+                        .get::<PhysHandle>(other)
+                        .ok()
+                        .and_then(|o_h| phys.contact_pair(h, *o_h, true))
+                        .is_some();
+                    if let (false, Some(contacts)) = (touching, contacts.as_mut()) {
+                        contacts.remove(&other);
+                    }
+                }
+                if bullet.stopped {
+                    bullet.stopped = false;
+                    force.vec = na::zero();
+                    movement = na::zero();
+                }
+
+                if let Some((other, distance)) = sweep(phys, h, movement) {
+                    movement *= distance / movement.magnitude();
+                    if let Some(contacts) = contacts.as_mut() {
+                        contacts.insert(other);
+                    }
+                    bullet.impacts.push(other);
+                    bullet.stopped = true;
+                }
+            }
+
             let obj = phys.get_mut(h)?;
             let mut iso = obj.position().clone();
 
-            iso.translation.vector += force.vec;
+            iso.translation.vector += movement;
 
             force.vec *= force.decay;
 
             obj.set_position_with_prediction(iso.clone(), {
-                iso.translation.vector += force.vec;
+                iso.translation.vector += force.vec * slow;
                 iso
             });
 
@@ -547,9 +755,13 @@ pub fn chase(world: &mut Game) {
             let obj = phys.get_mut(h)?;
             let mut iso = obj.position().clone();
 
-            iso.translation.vector -= iso.rotation * -na::Vector2::y() * speed;
+            let step = iso.rotation * na::Vector2::y() * speed;
+            iso.translation.vector += step;
 
-            obj.set_position(iso);
+            obj.set_position_with_prediction(iso.clone(), {
+                iso.translation.vector += step;
+                iso
+            });
 
             Some(())
         })();
@@ -565,12 +777,28 @@ pub fn chase(world: &mut Game) {
             let delta = na::Unit::new_normalize(iso.translation.vector - look_at_loc);
             let current = na::Unit::new_unchecked(iso.rotation * na::Vector2::x());
 
-            iso.rotation *=
-                na::UnitComplex::from_angle(look_chase.speed * delta.dot(&current).signum());
+            let turn = na::UnitComplex::from_angle(look_chase.speed * delta.dot(&current).signum());
+            iso.rotation *= turn;
 
-            obj.set_position(iso);
+            obj.set_position_with_prediction(iso.clone(), {
+                iso.rotation *= turn;
+                iso
+            });
 
             Some(())
         })();
     }
 }
+
+#[test]
+fn velocity_max_speed() {
+    let vel = Velocity {
+        max_speed: Some(0.5),
+        ..Velocity::new(na::Vector2::new(3.0, 4.0))
+    };
+    assert!((vel.capped(vel.vec) - na::Vector2::new(0.3, 0.4)).magnitude() < 1e-6);
+    assert_eq!(
+        vel.capped(na::Vector2::new(0.1, 0.0)),
+        na::Vector2::new(0.1, 0.0)
+    );
+}
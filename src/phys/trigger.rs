@@ -0,0 +1,160 @@
+use super::{Collide, CollisionWorld, Hitbox, PhysHandle};
+use crate::world::script;
+use fxhash::FxHashSet;
+use hecs::Entity;
+use std::collections::HashSet;
+
+/// A volume that notices things walking into and out of it, but never pushes anything around.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TriggerConfig {
+    pub hitbox: Hitbox,
+    /// Where the Trigger is relative to its Entity.
+    #[serde(default)]
+    pub offset: na::Vector2<f32>,
+    /// Only things that belong to at least one of these groups set off the Trigger.
+    pub detects: HashSet<Collide>,
+}
+impl Default for TriggerConfig {
+    fn default() -> Self {
+        Self {
+            hitbox: Hitbox::Cuboid(na::Vector2::new(1.0, 1.0)),
+            offset: na::zero(),
+            detects: [Collide::new("Player")].iter().cloned().collect(),
+        }
+    }
+}
+impl TriggerConfig {
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.collapsing("shape", |ui| self.hitbox.dev_ui(ui));
+
+        ui.label("offset");
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::f32(&mut self.offset.x).speed(0.001));
+            ui.add(egui::DragValue::f32(&mut self.offset.y).speed(0.001));
+        });
+
+        ui.collapsing("detects", |ui| {
+            for name in glsp::lib::<super::CollideTable>().names() {
+                let collide = Collide::new(name);
+                let mut detected = self.detects.contains(&collide);
+                ui.checkbox(name, &mut detected);
+                if detected {
+                    self.detects.insert(collide);
+                } else {
+                    self.detects.remove(&collide);
+                }
+            }
+        });
+
+        *self != before
+    }
+}
+
+/// The non-solid collision object an Entity's TriggerConfig was turned into,
+/// and what's currently inside of it.
+pub struct Trigger {
+    pub handle: PhysHandle,
+    offset: na::Vector2<f32>,
+    pub inside: FxHashSet<Entity>,
+}
+
+/// Gives `entity` a Trigger, centered `config.offset` away from `iso`.
+/// None if `config.hitbox` isn't a valid shape.
+pub fn trigger_insert(
+    ecs: &mut hecs::World,
+    phys: &mut CollisionWorld,
+    entity: Entity,
+    iso: na::Isometry2<f32>,
+    config: &TriggerConfig,
+) -> Option<PhysHandle> {
+    let detects = config.detects.iter().map(|c| &*c.0).collect::<Vec<_>>();
+    let (handle, _) = phys.add(
+        iso * na::Translation2::from(config.offset),
+        config.hitbox.shape()?,
+        super::trigger_groups(&detects),
+        ncollide2d::pipeline::GeometricQueryType::Proximity(0.0),
+        entity,
+    );
+    let trigger = Trigger {
+        handle,
+        offset: config.offset,
+        inside: FxHashSet::default(),
+    };
+    if let Err(e) = ecs.insert_one(entity, trigger) {
+        glsp::eprn!("Couldn't add Trigger: {}", e);
+        phys.remove(&[handle]);
+        return None;
+    }
+    Some(handle)
+}
+
+/// Keeps Triggers on physical Entities stuck to them as they move.
+pub(super) fn follow(ecs: &hecs::World, phys: &mut CollisionWorld) {
+    for (_, (trigger, &h)) in ecs.query::<(&Trigger, &PhysHandle)>().iter() {
+        let iso = match phys.collision_object(h) {
+            Some(obj) => *obj.position(),
+            None => continue,
+        };
+        if let Some(obj) = phys.get_mut(trigger.handle) {
+            obj.set_position(iso * na::Translation2::from(trigger.offset));
+        }
+    }
+}
+
+/// Records what went into and out of Triggers since the last update,
+/// so that scripts can hear about it.
+pub(super) fn proximity(ecs: &hecs::World, phys: &CollisionWorld, scripts: &mut script::Cache) {
+    use ncollide2d::query::Proximity::Intersecting;
+
+    for event in phys.proximity_events().iter() {
+        let entered = match (event.prev_status, event.new_status) {
+            (Intersecting, Intersecting) => continue,
+            (_, Intersecting) => true,
+            (Intersecting, _) => false,
+            _ => continue,
+        };
+
+        let handles = [
+            (event.collider1, event.collider2),
+            (event.collider2, event.collider1),
+        ];
+        for &(trigger_h, other_h) in handles.iter() {
+            // things that were just removed won't have objects anymore
+            let (trigger_ent, other_ent) = match (
+                phys.collision_object(trigger_h),
+                phys.collision_object(other_h),
+            ) {
+                (Some(t), Some(o)) => (*t.data(), *o.data()),
+                _ => continue,
+            };
+            let other_is_trigger = ecs
+                .get::<Trigger>(other_ent)
+                .map_or(false, |t| t.handle == other_h);
+            if trigger_ent == other_ent || other_is_trigger {
+                continue;
+            }
+
+            let mut trigger = match ecs.get_mut::<Trigger>(trigger_ent) {
+                Ok(t) if t.handle == trigger_h => t,
+                _ => continue,
+            };
+            if entered {
+                trigger.inside.insert(other_ent);
+                scripts.triggers_entered.push((trigger_ent, other_ent));
+            } else {
+                trigger.inside.remove(&other_ent);
+                scripts.triggers_exited.push((trigger_ent, other_ent));
+            }
+        }
+    }
+
+    // whatever died while inside of a Trigger has no reason to stay there
+    for (_, trigger) in ecs.query::<&mut Trigger>().iter() {
+        trigger.inside.retain(|&e| ecs.contains(e));
+    }
+}
@@ -0,0 +1,286 @@
+use super::{prefab::Comp, script, Game};
+
+/// A rectangle in world space.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Region {
+    pub min: na::Vector2<f32>,
+    pub max: na::Vector2<f32>,
+}
+impl Region {
+    pub fn contains(&self, p: na::Vector2<f32>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    fn random_point(&self) -> na::Vector2<f32> {
+        use macroquad::rand::gen_range;
+        na::Vector2::new(
+            gen_range(self.min.x, self.max.x),
+            gen_range(self.min.y, self.max.y),
+        )
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = *self;
+
+        ui.label("min");
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::f32(&mut self.min.x).speed(0.01));
+            ui.add(egui::DragValue::f32(&mut self.min.y).speed(0.01));
+        });
+        ui.label("max");
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::f32(&mut self.max.x).speed(0.01));
+            ui.add(egui::DragValue::f32(&mut self.max.y).speed(0.01));
+        });
+
+        *self != before
+    }
+}
+
+/// Some number of instances of a prefab, scattered about a Region.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnConfig {
+    pub prefab: String,
+    pub count: usize,
+    pub region: Region,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveConfig {
+    /// How many frames to wait after the last wave is cleared, or the Encounter begins,
+    /// before this wave spawns.
+    pub delay: u16,
+    pub spawns: Vec<SpawnConfig>,
+}
+
+/// Groups of prefab instances spawned in waves,
+/// each wave spawning only once everything from the last one has died.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncounterConfig {
+    /// Passed to the `encounter-complete` hook once the last wave is cleared.
+    pub name: String,
+    /// If set, the Encounter doesn't begin until the player walks into this Region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Region>,
+    pub waves: Vec<WaveConfig>,
+}
+impl EncounterConfig {
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.label("name");
+        ui.add(egui::TextEdit::new(&mut self.name));
+
+        let mut triggered = self.trigger.is_some();
+        ui.checkbox("trigger area", &mut triggered)
+            .tooltip_text("does the encounter wait for the player to walk somewhere?");
+        match (triggered, &mut self.trigger) {
+            (true, Some(region)) => {
+                region.dev_ui(ui);
+            }
+            (true, trigger) => {
+                *trigger = Some(Region {
+                    min: na::Vector2::new(-1.0, -1.0),
+                    max: na::Vector2::new(1.0, 1.0),
+                })
+            }
+            (false, trigger) => *trigger = None,
+        }
+
+        let mut removal_index: Option<usize> = None;
+        for (i, wave) in self.waves.iter_mut().enumerate() {
+            ui.collapsing(format!("wave {}", i + 1), |ui| {
+                ui.label("delay");
+                let mut delay = wave.delay as f32;
+                ui.add(egui::DragValue::f32(&mut delay));
+                wave.delay = delay.max(0.0).round() as u16;
+
+                let mut spawn_removal_index: Option<usize> = None;
+                for (j, spawn) in wave.spawns.iter_mut().enumerate() {
+                    ui.collapsing(format!("spawn {}", j + 1), |ui| {
+                        ui.label("prefab");
+                        ui.add(egui::TextEdit::new(&mut spawn.prefab));
+                        ui.label("count");
+                        let mut count = spawn.count as f32;
+                        ui.add(egui::DragValue::f32(&mut count));
+                        spawn.count = count.max(0.0) as usize;
+                        spawn.region.dev_ui(ui);
+                        if ui.button("Remove Spawn").clicked {
+                            spawn_removal_index = Some(j);
+                        }
+                    });
+                }
+                if let Some(j) = spawn_removal_index {
+                    wave.spawns.remove(j);
+                }
+                if ui.button("Add Spawn").clicked {
+                    wave.spawns.push(SpawnConfig {
+                        prefab: "SlimeTarget".to_string(),
+                        count: 1,
+                        region: Region {
+                            min: na::Vector2::new(-1.0, -1.0),
+                            max: na::Vector2::new(1.0, 1.0),
+                        },
+                    });
+                }
+
+                if ui.button("Remove Wave").clicked {
+                    removal_index = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removal_index {
+            self.waves.remove(i);
+        }
+        if ui.button("Add Wave").clicked {
+            self.waves.push(WaveConfig {
+                delay: 60,
+                spawns: vec![],
+            });
+        }
+
+        *self != before
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Phase {
+    /// Waiting for the player to walk into the trigger area.
+    Waiting,
+    /// Counting down until the next wave spawns.
+    Delay {
+        wave: usize,
+        timer: u16,
+    },
+    /// Waiting for everything spawned by this wave to die.
+    Fighting {
+        wave: usize,
+        alive: Vec<hecs::Entity>,
+    },
+    Complete,
+}
+
+/// Keeps track of how far along each of the Config's Encounters are.
+#[derive(Default)]
+pub struct Encounters {
+    phases: Vec<Phase>,
+}
+impl Encounters {
+    /// Puts every Encounter back at the very start, e.g. after the instances are reset.
+    /// Whatever they've spawned should be killed separately.
+    pub fn reset(&mut self) {
+        self.phases.clear();
+    }
+
+    #[cfg(feature = "confui")]
+    pub fn status(&self, index: usize) -> String {
+        match self.phases.get(index) {
+            None | Some(Phase::Waiting) => "waiting".to_string(),
+            Some(Phase::Delay { wave, .. }) => format!("wave {} incoming", wave + 1),
+            Some(Phase::Fighting { wave, alive }) => {
+                format!("wave {}, {} left", wave + 1, alive.len())
+            }
+            Some(Phase::Complete) => "complete".to_string(),
+        }
+    }
+}
+
+/// Spawns each Encounter's waves once the one before it has been cleared,
+/// letting scripts know via `encounter-complete` when there are no more waves left.
+pub fn encounters(
+    Game {
+        ecs,
+        phys,
+        dead,
+        player,
+        tag_bank,
+        instance_tracker,
+        encounters,
+        config,
+        ..
+    }: &mut Game,
+) {
+    // whatever was spawned is dying, there'll be a fresh start once it's all gone
+    if instance_tracker.resetting() {
+        return;
+    }
+
+    let configs = &config.encounters;
+    encounters.phases.resize(configs.len(), Phase::Waiting);
+
+    let player_loc = phys
+        .collision_object(player.phys_handle)
+        .map(|obj| obj.position().translation.vector);
+
+    for (phase, encounter) in encounters.phases.iter_mut().zip(configs.iter()) {
+        let next = match phase {
+            Phase::Waiting => {
+                let triggered = match (encounter.trigger, player_loc) {
+                    (Some(region), Some(loc)) => region.contains(loc),
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                };
+                if !triggered {
+                    continue;
+                }
+                Phase::Delay { wave: 0, timer: 0 }
+            }
+            Phase::Delay { wave, timer } => match encounter.waves.get(*wave) {
+                None => {
+                    glsp::lib_mut::<script::Cache>()
+                        .completed_encounters
+                        .push(encounter.name.clone());
+                    Phase::Complete
+                }
+                Some(wave_config) if *timer >= wave_config.delay => {
+                    let mut alive = vec![];
+                    for spawn in &wave_config.spawns {
+                        let pf_key =
+                            match instance_tracker.prefab_key(&config.prefab, &spawn.prefab) {
+                                Some(k) => k,
+                                None => {
+                                    glsp::eprn!(
+                                        "{} encounter can't spawn {}, no prefab by that name",
+                                        encounter.name,
+                                        spawn.prefab
+                                    );
+                                    continue;
+                                }
+                            };
+                        for _ in 0..spawn.count {
+                            let comps = [Comp::Position(spawn.region.random_point())];
+                            let tag = instance_tracker
+                                .spawn_dynamic(ecs, phys, tag_bank, config, pf_key, &comps);
+                            alive.push(tag.entity);
+                        }
+                    }
+                    Phase::Fighting { wave: *wave, alive }
+                }
+                Some(_) => {
+                    *timer += 1;
+                    continue;
+                }
+            },
+            Phase::Fighting { wave, alive } => {
+                alive.retain(|&e| instance_tracker.tag(e).is_some() && !dead.is_marked(e));
+                if !alive.is_empty() {
+                    continue;
+                }
+                Phase::Delay {
+                    wave: *wave + 1,
+                    timer: 0,
+                }
+            }
+            Phase::Complete => continue,
+        };
+        *phase = next;
+    }
+}
@@ -4,15 +4,17 @@ use macroquad::*;
 
 use crate::{
     combat, draw,
-    phys::{self, collision, CollisionGroups, CollisionWorld, Cuboid, PhysHandle},
+    phys::{self, collision, CollisionGroups, CollisionWorld, PhysHandle, ShapeHandle},
 };
 
 pub mod player;
 pub use player::Player;
 pub mod map;
 pub use map::Map;
+pub mod encounter;
 pub mod prefab;
 pub mod script;
+pub mod stats;
 
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -37,6 +39,16 @@ pub struct Config {
     #[cfg(feature = "confui")]
     #[serde(skip)]
     pub instances_expanded: bool,
+    #[serde(default)]
+    pub encounters: Vec<encounter::EncounterConfig>,
+    #[cfg(feature = "confui")]
+    #[serde(skip)]
+    pub encounters_expanded: bool,
+    #[serde(default)]
+    pub collision: phys::CollideConfig,
+    #[cfg(feature = "confui")]
+    #[serde(skip)]
+    pub collision_expanded: bool,
 }
 #[cfg(feature = "confui")]
 pub fn dev_ui(ui_plugin: &mut emigui_miniquad::UiPlugin, world: &mut Game) {
@@ -59,6 +71,8 @@ pub fn dev_ui(ui_plugin: &mut emigui_miniquad::UiPlugin, world: &mut Game) {
                 ui.checkbox("Player", &mut world.config.player_expanded);
                 ui.checkbox("Prefabs", &mut world.config.prefabs_expanded);
                 ui.checkbox("Instances", &mut world.config.instances_expanded);
+                ui.checkbox("Encounters", &mut world.config.encounters_expanded);
+                ui.checkbox("Collision Groups", &mut world.config.collision_expanded);
             });
         });
 
@@ -77,9 +91,23 @@ pub fn dev_ui(ui_plugin: &mut emigui_miniquad::UiPlugin, world: &mut Game) {
         }
 
         if world.config.player_expanded {
+            let mut weapons_before = None;
             egui::Window::new("Player")
                 .default_pos(egui::pos2(0.0, 150.0))
-                .show(ui.ctx(), |ui| world.config.player.dev_ui(ui));
+                .show(ui.ctx(), |ui| {
+                    let Config { player, draw, .. } = &mut world.config;
+                    weapons_before = player.dev_ui(ui, draw);
+                });
+
+            // the weapon in the player's hand might have just been moved, edited or removed
+            if let Some(before) = weapons_before {
+                world.player.fit_weapon_index(
+                    &mut world.ecs,
+                    &mut world.dead,
+                    &world.config.player,
+                    &before,
+                );
+            }
         }
 
         if world.config.prefabs_expanded {
@@ -95,6 +123,55 @@ pub fn dev_ui(ui_plugin: &mut emigui_miniquad::UiPlugin, world: &mut Game) {
                     prefab::instances::dev_ui(ui, world);
                 });
         }
+
+        if world.config.encounters_expanded {
+            egui::Window::new("Encounters")
+                .default_pos(egui::pos2(0.0, 300.0))
+                .show(ui.ctx(), |ui| {
+                    let Game {
+                        config, encounters, ..
+                    } = world;
+                    let mut removal_index: Option<usize> = None;
+                    let mut dirty = false;
+                    for (i, encounter) in config.encounters.iter_mut().enumerate() {
+                        ui.collapsing(
+                            format!("{} ({})", encounter.name, encounters.status(i)),
+                            |ui| {
+                                dirty |= encounter.dev_ui(ui);
+                                if ui.button("Remove Encounter").clicked {
+                                    removal_index = Some(i);
+                                }
+                            },
+                        );
+                    }
+                    if let Some(i) = removal_index {
+                        config.encounters.remove(i);
+                        dirty = true;
+                    }
+                    // the Encounters in progress might refer to waves that aren't there anymore
+                    if dirty {
+                        encounters.reset();
+                    }
+                    if ui.button("Add Encounter").clicked {
+                        config.encounters.push(encounter::EncounterConfig {
+                            name: format!("encounter {}", config.encounters.len() + 1),
+                            trigger: None,
+                            waves: vec![],
+                        });
+                    }
+                });
+        }
+
+        if world.config.collision_expanded {
+            egui::Window::new("Collision Groups")
+                .default_pos(egui::pos2(0.0, 350.0))
+                .show(ui.ctx(), |ui| {
+                    let collision = &mut world.config.collision;
+                    if collision.dev_ui(ui) {
+                        *glsp::lib_mut::<phys::CollideTable>() = phys::CollideTable::new(collision);
+                    }
+                });
+        }
     });
 }
 
@@ -116,8 +193,12 @@ impl World {
         let config: Config = ron::de::from_reader(&*load_file("config.ron").await.unwrap()).unwrap();
         let images = draw::Images::load(&config.draw).await;
         glsp_runtime.run(move || {
+            glsp::add_lib(phys::CollideTable::new(&config.collision));
             glsp::add_lib(script::Intake::new());
-            match glsp::load("script/entry.glsp").and_then(|c| script::Cache::new(&c)) {
+            match script::sequence::prelude()
+                .and_then(|_| glsp::load("script/entry.glsp"))
+                .and_then(|c| script::Cache::new(&c))
+            {
                 Ok(script_cache) => glsp::add_lib(script_cache),
                 Err(e) => eprn!("couldn't load glsp: {}", e),
             }
@@ -202,7 +283,6 @@ impl World {
         });
     }
 }
-
 /// Marks Entities to be deleted at the end of the frame.
 pub struct Dead {
     marks: fxhash::FxHashSet<hecs::Entity>,
@@ -244,7 +324,10 @@ glsp::lib! {
         pub tag_bank: script::TagBank,
         pub images: draw::Images,
         pub draw_state: draw::DrawState,
+        pub feedback: draw::Feedback,
         pub instance_tracker: prefab::InstanceTracker,
+        pub encounters: encounter::Encounters,
+        pub stats: stats::Stats,
     }
 }
 impl l8r::ContainsHecsWorld for Game {
@@ -270,7 +353,10 @@ impl Game {
             tag_bank: script::TagBank::new(),
             images,
             draw_state: Default::default(),
+            feedback: Default::default(),
             instance_tracker: Default::default(),
+            encounters: Default::default(),
+            stats: Default::default(),
             config,
             phys,
             ecs,
@@ -289,10 +375,10 @@ impl Game {
         &mut self,
         entity: hecs::Entity,
         iso: na::Isometry2<f32>,
-        cuboid: Cuboid<f32>,
+        shape: ShapeHandle<f32>,
         groups: CollisionGroups,
     ) -> PhysHandle {
-        phys::phys_insert(&mut self.ecs, &mut self.phys, entity, iso, cuboid, groups)
+        phys::phys_insert(&mut self.ecs, &mut self.phys, entity, iso, shape, groups)
     }
 
     fn update(&mut self, ignore_inputs: IgnoreInputs) {
@@ -302,7 +388,21 @@ impl Game {
             prefab::clear_removed_prefabs(self);
         }
 
-        if !self.player.state.is_throwing() && !ignore_inputs.keyboard {
+        let player_dead = self.player.state.is_dead();
+        if !player_dead {
+            self.stats.tick();
+        }
+        let player_stunned = self
+            .ecs
+            .get::<combat::StatusEffects>(self.player.entity)
+            .map_or(false, |fx| fx.is_stunned());
+
+        if !self.player.state.is_throwing()
+            && !self.player.state.is_swinging()
+            && !player_dead
+            && !player_stunned
+            && !ignore_inputs.keyboard
+        {
             player::movement(self);
         }
 
@@ -310,12 +410,25 @@ impl Game {
         phys::chase(self);
         collision::collision(self);
 
-        if !ignore_inputs.mouse {
-            player::aiming(self);
+        // swinging comes first so that a right click meant to cancel a throw doesn't start a swing
+        player::melee(self, !ignore_inputs.mouse && !player_dead);
+        if !player_dead && !self.player.state.is_swinging() {
+            player::switch_weapons(self, !ignore_inputs.keyboard, !ignore_inputs.mouse);
+            if !ignore_inputs.mouse {
+                player::aiming(self);
+            }
         }
+        player::boomerang(self);
+        player::recovery(self);
 
+        combat::tick_hit_timers(self);
+        combat::status::tick_status_effects(self);
         combat::hurtful_damage(self);
         combat::health::remove_out_of_health(self);
+        player::death(self);
+        prefab::instances::finish_reset(self);
+        encounter::encounters(self);
+        draw::feedback::update(self);
 
         draw::animate(self);
         draw::clear_ghosts(self);
@@ -1,11 +1,11 @@
 use crate::{
-    draw,
+    combat, draw,
     phys::{self, PhysHandle},
     world, Game,
 };
 use macroquad::*;
 
-#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(transparent)]
 pub struct Rot(pub f32);
 
@@ -30,7 +30,7 @@ impl Into<na::Unit<na::Vector2<f32>>> for Rot {
 /// Instead of processing rotations as `UnitComplex`es,
 /// this function treats them as `na::Vector2`s, for ease of lerping
 /// among a host of other factors.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Keyframe {
     pub time: f32,
@@ -52,10 +52,55 @@ pub enum KeyframeDevUiEvent {
 }
 
 impl Keyframe {
-    fn into_iso2(self) -> na::Isometry2<f32> {
+    pub(super) fn into_iso2(self) -> na::Isometry2<f32> {
         na::Isometry2::new(self.pos, self.rot.0)
     }
 
+    /// Blends between the two keyframes on either side of `prog`,
+    /// using `last` as the frame after all of the `keyframes`.
+    ///
+    /// # Panics
+    /// If there aren't any `keyframes`.
+    pub(super) fn interpolate(keyframes: &[Keyframe], mut prog: f32, last: &Keyframe) -> Keyframe {
+        let mut frames = keyframes.iter();
+
+        // find the key frames before and after our current time
+        let mut lf = frames.next().unwrap();
+        let rf = frames
+            .find_map(|rf| {
+                if rf.time > prog {
+                    // short circuit, we found the first frame with a higher timestamp
+                    Some(rf)
+                } else {
+                    // not high enough, but maybe it's a lower bound?
+                    lf = rf;
+                    None
+                }
+            })
+            .unwrap_or(last);
+
+        // there's nothing left to blend towards
+        if rf.time <= lf.time {
+            return rf.clone();
+        }
+
+        // scale prog according to how close to rf.time it is from lf.time
+        // i.e. 1 would mean it's literally rf.time, 0 is literally lf.time
+        prog = (prog - lf.time) / (rf.time - lf.time);
+
+        Keyframe {
+            time: prog,
+            pos: lf.pos.lerp(&rf.pos, prog),
+            rot: Rot::from_unit(lf.rot.as_unit().slerp(&rf.rot.into(), prog)),
+            bottom_offset: lf.bottom_offset + (rf.bottom_offset - lf.bottom_offset) * prog,
+            behind_wielder: rf.behind_wielder,
+            #[cfg(feature = "confui")]
+            removal_checkbox_checked: false,
+            #[cfg(feature = "confui")]
+            removal_checkbox_out: false,
+        }
+    }
+
     #[cfg(feature = "confui")]
     pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> Option<KeyframeDevUiEvent> {
         if ui.button("remove keyframe").clicked {
@@ -102,22 +147,26 @@ enum WielderState {
     /// if you let go you'll go back to Loaded.
     Readying { timer: u16 },
 
-    /// Let go to fire!
-    /// TODO: A way to leave this stage (without firing).
-    Readied,
+    /// Let go to fire, or right click to put the weapon away without throwing it.
+    /// The longer you hold on, the more charge builds up, if the weapon can be charged.
+    Readied { charge: u16 },
 
     /// Lasts exactly one frame.
     /// During this frame, the projectile is launched.
-    Shooting,
+    Shooting { charge: u16 },
 }
 
 pub struct Wielder {
     state: WielderState,
+    /// After a throw is cancelled, the mouse button has to be let go of
+    /// before another one can be readied.
+    cancelled: bool,
 }
 impl Wielder {
     pub fn new() -> Self {
         Self {
             state: WielderState::Loaded,
+            cancelled: false,
         }
     }
 
@@ -125,11 +174,16 @@ impl Wielder {
     fn advance_state(
         &mut self,
         mouse_down: bool,
+        cancel: bool,
         weapon: &WeaponConfig,
         readying_animation_length: u16,
     ) {
         use WielderState::*;
 
+        if !mouse_down {
+            self.cancelled = false;
+        }
+
         self.state = match self.state {
             Reloading { mut timer } => {
                 timer += 1;
@@ -140,7 +194,7 @@ impl Wielder {
                 }
             }
             Loaded => {
-                if mouse_down {
+                if mouse_down && !self.cancelled {
                     Readying { timer: 0 }
                 } else {
                     Loaded
@@ -151,43 +205,80 @@ impl Wielder {
                 if !mouse_down {
                     Loaded
                 } else if timer >= readying_animation_length {
-                    Readied
+                    Readied { charge: 0 }
                 } else {
                     Readying { timer }
                 }
             }
-            Readied => {
+            Readied { .. } if cancel => {
+                self.cancelled = true;
+                match weapon.cancel_penalty {
+                    Some(penalty) => Reloading {
+                        timer: weapon.reload_time.saturating_sub(penalty),
+                    },
+                    None => Loaded,
+                }
+            }
+            Readied { charge } => {
                 if !mouse_down {
-                    Shooting
+                    Shooting { charge }
                 } else {
-                    Readied
+                    Readied {
+                        charge: charge.saturating_add(1),
+                    }
                 }
             }
-            Shooting => Reloading { timer: 0 },
+            Shooting { .. } => Reloading { timer: 0 },
         };
     }
 
     fn shooting(&self) -> bool {
-        self.state == WielderState::Shooting
+        matches!(self.state, WielderState::Shooting { .. })
+    }
+
+    /// How many frames the weapon has been held past Readied, if it's Readied.
+    pub(super) fn charge_frames(&self) -> Option<u16> {
+        match self.state {
+            WielderState::Readied { charge } => Some(charge),
+            _ => None,
+        }
+    }
+
+    /// How much the throw being launched this frame,
+    /// or the one that would be launched if the weapon were let go of now, should be scaled by.
+    fn throw_strength(&self, weapon: &WeaponConfig) -> f32 {
+        use WielderState::*;
+
+        match (self.state, &weapon.charge) {
+            (Shooting { charge }, Some(c)) | (Readied { charge }, Some(c)) => c.multiplier(charge),
+            _ => 1.0,
+        }
+    }
+
+    /// Not doing anything with the weapon, so it's safe to swap it out.
+    pub(super) fn idle(&self) -> bool {
+        self.state == WielderState::Loaded
     }
 }
 
+/// The groups a weapon collides with once it's been thrown.
 fn weapon_hitbox_groups() -> phys::CollisionGroups {
-    phys::CollisionGroups::new()
-        .with_membership(&[phys::Collide::Weapon as usize])
-        .with_whitelist(&[phys::Collide::World as usize, phys::Collide::Enemy as usize])
+    phys::collision_groups(Some(&["Weapon"]), Some(&["World", "Enemy"]), &[])
 }
+/// The groups a weapon collides with while it's still in the player's hands.
 fn weapon_prelaunch_groups() -> phys::CollisionGroups {
-    phys::CollisionGroups::new()
-        .with_membership(&[phys::Collide::Weapon as usize])
-        .with_blacklist(&[
-            phys::Collide::Player as usize,
-            phys::Collide::Enemy as usize,
-        ])
+    phys::collision_groups(Some(&["Weapon"]), None, &["Player", "Enemy"])
+}
+
+fn default_pickup_radius() -> f32 {
+    0.3
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct WeaponConfig {
+    pub(super) name: String,
+    art: draw::ArtHandle,
+
     // positioning
     offset: na::Vector2<f32>,
     screen_offset: na::Vector2<f32>,
@@ -195,26 +286,54 @@ pub struct WeaponConfig {
 
     // timing
     reload_time: u16,
+    /// If set, cancelling a readied throw means waiting this many frames before readying again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cancel_penalty: Option<u16>,
 
     // projectile
     force_magnitude: f32,
     /// Range [0, 1] unless you want your Weapon to get exponentially faster each frame.
     force_decay: f32,
-    hitbox_size: na::Vector2<f32>,
-    boomerang: bool,
-    #[serde(skip, default = "weapon_hitbox_groups")]
-    hitbox_groups: phys::CollisionGroups,
-    #[serde(skip, default = "weapon_prelaunch_groups")]
-    prelaunch_groups: phys::CollisionGroups,
+    hitbox: phys::Hitbox,
+    /// If set, the weapon flies back to the player after being thrown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) boomerang: Option<super::boomerang::BoomerangConfig>,
+
+    // charging
+    /// If set, holding the weapon once it's Readied makes for a stronger throw.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) charge: Option<super::charge::ChargeConfig>,
+
+    // damage
+    hurtful: combat::Hurtful,
+    knock_back: phys::KnockBackConfig,
 
     // side effects
     player_knock_back_force: f32,
     player_knock_back_decay: f32,
 
+    // recovery
+    /// If set, a thrown weapon that hasn't been picked up returns after this many frames.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) auto_return_time: Option<u16>,
+    /// How close the player must walk to a resting weapon to pick it up.
+    #[serde(default = "default_pickup_radius")]
+    pub(super) pickup_radius: f32,
+
     keyframes: Vec<Keyframe>,
     animation_art: draw::ArtHandle,
 }
 impl WeaponConfig {
+    /// Spawns an entity for the player to wield.
+    pub(super) fn spawn(&self, ecs: &mut hecs::World) -> hecs::Entity {
+        ecs.spawn((
+            draw::Looks::art(self.art),
+            self.hurtful.clone(),
+            combat::HitRecord::default(),
+            Into::<phys::KnockBack>::into(self.knock_back.clone()),
+        ))
+    }
+
     /// # Input
     /// Takes a unit vector representing the delta
     /// between the player's world position and the mouse.
@@ -255,7 +374,7 @@ impl WeaponConfig {
                 (timer as f32) / (readying_animation_length as f32),
                 &last,
             ),
-            WielderState::Readied | WielderState::Shooting => {
+            WielderState::Readied { .. } | WielderState::Shooting { .. } => {
                 //last.rot.0 = 0.0;
                 last.bottom_offset = 0.0;
                 last
@@ -263,43 +382,27 @@ impl WeaponConfig {
         })
     }
 
-    fn readying_animation_frame(&self, mut prog: f32, last: &Keyframe) -> Keyframe {
-        let mut frames = self.keyframes.iter();
+    fn readying_animation_frame(&self, prog: f32, last: &Keyframe) -> Keyframe {
+        Keyframe::interpolate(&self.keyframes, prog, last)
+    }
 
-        // find the key frames before and after our current time
-        let mut lf = frames.next().unwrap();
-        let rf = frames
-            .find_map(|rf| {
-                if rf.time > prog {
-                    // short circuit, we found the first frame with a higher timestamp
-                    Some(rf)
-                } else {
-                    // not high enough, but maybe it's a lower bound?
-                    lf = rf;
-                    None
-                }
-            })
-            .unwrap_or(last);
+    #[cfg(feature = "confui")]
+    /// Changes to the art and damage apply to weapons spawned afterwards.
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui, draw: &mut draw::Config) -> bool {
+        let before = self.clone();
+        let mut dirty = false;
 
-        // scale prog according to how close to rf.time it is from lf.time
-        // i.e. 1 would mean it's literally rf.time, 0 is literally lf.time
-        prog = (prog - lf.time) / (rf.time - lf.time);
+        ui.label("name");
+        ui.add(egui::TextEdit::new(&mut self.name));
 
-        Keyframe {
-            time: prog,
-            pos: lf.pos.lerp(&rf.pos, prog),
-            rot: Rot::from_unit(lf.rot.as_unit().slerp(&rf.rot.into(), prog)),
-            bottom_offset: lf.bottom_offset + (rf.bottom_offset - lf.bottom_offset) * prog,
-            behind_wielder: rf.behind_wielder,
-            #[cfg(feature = "confui")]
-            removal_checkbox_checked: false,
-            #[cfg(feature = "confui")]
-            removal_checkbox_out: false,
-        }
-    }
+        ui.collapsing("Art", |ui| draw.select_handle_dev_ui(ui, &mut self.art));
+
+        ui.collapsing("Damage", |ui| {
+            ui.collapsing("Hurtful", |ui| self.hurtful.dev_ui(ui));
+            ui.collapsing("Knock Back", |ui| self.knock_back.dev_ui(ui));
+        });
 
-    #[cfg(feature = "confui")]
-    pub fn dev_ui(&mut self, ui: &mut egui::Ui) {
         ui.collapsing("Default Position", |ui| {
             ui.label("bottom offset");
             ui.add(egui::DragValue::f32(&mut self.bottom_offset).speed(0.01));
@@ -322,22 +425,80 @@ impl WeaponConfig {
             let mut et = self.reload_time as f32;
             ui.add(egui::DragValue::f32(&mut et));
             self.reload_time = et.round() as u16;
+
+            let mut penalty = self.cancel_penalty.is_some();
+            ui.checkbox("cancel penalty", &mut penalty)
+                .tooltip_text("do you have to wait a bit after putting away a readied weapon?");
+            self.cancel_penalty = if penalty {
+                let mut cp = self.cancel_penalty.unwrap_or(20) as f32;
+                ui.add(egui::DragValue::f32(&mut cp));
+                Some(cp.max(0.0).round() as u16)
+            } else {
+                None
+            };
         });
 
         ui.collapsing("Projectile", |ui| {
-            ui.label("hitbox size");
-            ui.horizontal(|ui| {
-                ui.add(egui::DragValue::f32(&mut self.hitbox_size.x).speed(0.01));
-                ui.add(egui::DragValue::f32(&mut self.hitbox_size.y).speed(0.01));
-            });
+            ui.collapsing("Hitbox", |ui| self.hitbox.dev_ui(ui));
 
             ui.label("force magnitude");
             ui.add(egui::DragValue::f32(&mut self.force_magnitude).speed(0.01));
 
             ui.add(egui::Slider::f32(&mut self.force_decay, 0.0..=1.0).text("force decay"));
+        });
 
-            ui.checkbox("boomerang", &mut self.boomerang)
-                .tooltip_text("do you automatically get this weapon back after having thrown it?");
+        ui.collapsing("Boomerang", |ui| {
+            let mut boomerang = self.boomerang.is_some();
+            ui.checkbox("boomerang", &mut boomerang)
+                .tooltip_text("does this weapon fly back to you after having thrown it?");
+            match (boomerang, &mut self.boomerang) {
+                (true, Some(config)) => dirty |= config.dev_ui(ui),
+                (true, config) => {
+                    *config = Some(super::boomerang::BoomerangConfig {
+                        distance: 3.0,
+                        curvature: 0.05,
+                        return_speed: 0.15,
+                        catch_radius: 0.4,
+                    })
+                }
+                (false, config) => *config = None,
+            }
+        });
+
+        ui.collapsing("Charge", |ui| {
+            let mut chargeable = self.charge.is_some();
+            ui.checkbox("chargeable", &mut chargeable).tooltip_text(
+                "does holding the weapon after it's readied make for a stronger throw?",
+            );
+            match (chargeable, &mut self.charge) {
+                (true, Some(charge)) => dirty |= charge.dev_ui(ui),
+                (true, charge) => {
+                    *charge = Some(super::charge::ChargeConfig {
+                        time: 60,
+                        curve: super::charge::ChargeCurve::Linear,
+                        minimum: 1.0,
+                        maximum: 2.0,
+                        overcharge: None,
+                    })
+                }
+                (false, charge) => *charge = None,
+            }
+        });
+
+        ui.collapsing("Recovery", |ui| {
+            ui.label("pickup radius");
+            ui.add(egui::DragValue::f32(&mut self.pickup_radius).speed(0.01));
+
+            let mut auto_return = self.auto_return_time.is_some();
+            ui.checkbox("auto return", &mut auto_return)
+                .tooltip_text("does the weapon come back on its own after a while?");
+            self.auto_return_time = if auto_return {
+                let mut rt = self.auto_return_time.unwrap_or(180) as f32;
+                ui.add(egui::DragValue::f32(&mut rt));
+                Some(rt.max(0.0).round() as u16)
+            } else {
+                None
+            };
         });
 
         ui.collapsing("Side Effects", |ui| {
@@ -350,25 +511,29 @@ impl WeaponConfig {
             );
         });
 
-        ui.collapsing("Keyframes", |ui| {
-            let dead_index: Option<usize> = self
-                .keyframes
-                .iter_mut()
-                .enumerate()
-                .filter_map(|(i, kf)| {
-                    ui.collapsing(format!("keyframe {}", i), |ui| match kf.dev_ui(ui) {
-                        Some(KeyframeDevUiEvent::Remove) => Some(i),
-                        None => None,
-                    })
-                    .and_then(|x| x)
-                })
-                // there can only ever be one removed per frame, so ...
-                .next();
+        ui.collapsing("Keyframes", |ui| keyframes_dev_ui(ui, &mut self.keyframes));
 
-            if let Some(i) = dead_index {
-                self.keyframes.remove(i);
-            }
-        });
+        dirty || *self != before
+    }
+}
+
+#[cfg(feature = "confui")]
+pub(super) fn keyframes_dev_ui(ui: &mut egui::Ui, keyframes: &mut Vec<Keyframe>) {
+    let dead_index: Option<usize> = keyframes
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(i, kf)| {
+            ui.collapsing(format!("keyframe {}", i), |ui| match kf.dev_ui(ui) {
+                Some(KeyframeDevUiEvent::Remove) => Some(i),
+                None => None,
+            })
+            .and_then(|x| x)
+        })
+        // there can only ever be one removed per frame, so ...
+        .next();
+
+    if let Some(i) = dead_index {
+        keyframes.remove(i);
     }
 }
 
@@ -384,16 +549,20 @@ pub fn aiming(
             world::Config {
                 #[cfg(feature = "confui")]
                 draw_debug,
-                player: world::player::Config { weapon, .. },
+                player: player_config,
                 draw: draw_config,
                 ..
             },
+        stats,
         player:
             world::Player {
                 entity: wielder_ent,
                 phys_handle: wielder_h,
                 state: player_state,
                 weapon_entity,
+                weapon_index,
+                thrown,
+                trajectory,
                 wielder,
                 walk_animator,
                 ..
@@ -401,6 +570,7 @@ pub fn aiming(
         ..
     }: &mut Game,
 ) -> Option<()> {
+    let weapon = player_config.weapon(*weapon_index)?;
     let wielder_iso = phys.collision_object(*wielder_h)?.position();
     let wielder_flipped = ecs.get::<draw::Looks>(*wielder_ent).ok()?.flip_x;
     let wep_ent = weapon_entity.clone()?;
@@ -429,6 +599,8 @@ pub fn aiming(
         mouse_pos - wep_screen_pos
     });
     let mouse_down = is_mouse_button_down(MouseButton::Left);
+    let cancel = matches!(wielder.state, WielderState::Readied { .. })
+        && is_mouse_button_pressed(MouseButton::Right);
 
     let readying_animation_length = match draw_config.get(weapon.animation_art).spritesheet {
         Some(ss) => (ss.total.get() * ss.frame_rate.get()) as u16 - 2,
@@ -441,7 +613,7 @@ pub fn aiming(
 
         let frame = match wielder.state {
             WielderState::Readying { timer } => Some(timer),
-            WielderState::Readied => Some(readying_animation_length),
+            WielderState::Readied { .. } => Some(readying_animation_length),
             _ => None,
         };
         if let Some(f) = frame {
@@ -453,15 +625,16 @@ pub fn aiming(
             looks.flip_x = delta.x < 0.0;
 
             // if we're leaving these states it's important to give animation control back to walking
-            if !mouse_down {
+            if !mouse_down || cancel {
                 walk_animator.direction = super::Direction::Side;
                 *player_state = super::PlayerState::Walking;
             }
         };
     }
 
-    // if they quit preparing to throw in the middle, we need to unphysicalize the weapon
-    if matches!(wielder.state, WielderState::Readying { .. }) && !mouse_down {
+    // if they quit preparing to throw in the middle or cancel the throw,
+    // we need to unphysicalize the weapon
+    if (matches!(wielder.state, WielderState::Readying { .. }) && !mouse_down) || cancel {
         if let Ok(&wep_h) = ecs.get::<PhysHandle>(wep_ent).as_deref() {
             l8r.l8r(move |w| w.remove_physical(wep_ent, wep_h))
         }
@@ -472,17 +645,12 @@ pub fn aiming(
 
         let mut wep_looks = ecs.get_mut::<draw::Looks>(wep_ent).ok()?;
 
-        // make sure boomerang weapons don't render over the player when they're laying on the ground
-        if weapon.boomerang && matches!(wielder.state, Readied) {
-            wep_looks.z_offset = 0.0;
-        }
-
-        wielder.advance_state(mouse_down, &weapon, readying_animation_length);
+        wielder.advance_state(mouse_down, cancel, &weapon, readying_animation_length);
         let frame = weapon.animation_frame(delta, wielder.state, readying_animation_length)?;
 
         wep_looks.bottom_offset = frame.bottom_offset;
         wep_looks.z_offset = match (wielder.state, mouse_down) {
-            (Readied, true) => 10.0,
+            (Readied { .. }, true) => 10.0,
             (Readying { .. }, true) if !frame.behind_wielder => 10.0,
             _ => 0.0,
         };
@@ -496,26 +664,47 @@ pub fn aiming(
         frame_iso.translation.vector.x *= -1.0;
     }
     frame_iso.translation.vector += wielder_iso.translation.vector;
+    let shape = weapon.hitbox.shape()?;
     let wep_h = *ecs.get::<PhysHandle>(wep_ent).ok().or_else(|| {
-        let groups = weapon.prelaunch_groups.clone();
-        let shape = ncollide2d::shape::Cuboid::new(weapon.hitbox_size.clone());
+        let groups = weapon_prelaunch_groups();
+        let shape = shape.clone();
         l8r.l8r(move |world| drop(world.make_physical(wep_ent, frame_iso, shape, groups)));
         None
     })?;
 
-    if matches!(wielder.state, WielderState::Readying { timer: 0 }) && weapon.boomerang {
-        #[allow(deprecated)]
-        phys.set_collision_groups(wep_h, weapon.prelaunch_groups);
-    }
+    // show where the weapon would go if it were let go of right now
+    *trajectory = match wielder.state {
+        WielderState::Readying { .. } | WielderState::Readied { .. } => {
+            Some(super::trajectory::predict(
+                phys,
+                frame_iso,
+                delta.into_inner() * weapon.force_magnitude * wielder.throw_strength(weapon),
+                weapon.force_decay,
+                weapon.boomerang.as_ref(),
+                &shape,
+                &weapon_hitbox_groups(),
+            ))
+        }
+        _ => None,
+    };
 
     let wep_obj = phys.get_mut(wep_h)?;
     wep_obj.set_position(frame_iso);
 
     // fire the spear if the wielder state indicates to do so!
     if wielder.shooting() {
+        let strength = wielder.throw_strength(weapon);
+
+        stats.thrown(wep_ent);
+
         // cut off ties between weapon/player
-        if !weapon.boomerang {
-            *weapon_entity = None;
+        *weapon_entity = None;
+        *thrown = Some(super::recovery::Thrown::new(wep_ent));
+        if weapon.boomerang.is_some() {
+            l8r.insert_one(
+                wep_ent,
+                super::boomerang::Flight::new(frame_iso.translation.vector),
+            );
         }
 
         // let walking regain control of animating the wielder
@@ -533,18 +722,31 @@ pub fn aiming(
 
         // the spear needs to go forward and run into things now.
         //
-        // damage isn't configured here because the spear was Hurtful the entire time,
-        // it's only now even able to collide with things.
-        wep_obj.set_collision_groups(weapon.hitbox_groups);
+        // the spear was Hurtful the entire time, it's only now even able to collide with things,
+        // but how much it hurts depends on how long the throw was charged.
+        wep_obj.set_collision_groups(weapon_hitbox_groups());
+
+        let mut hurtful = weapon.hurtful.clone();
+        hurtful.raw_damage *= strength;
+        l8r.insert_one(wep_ent, hurtful);
+
+        let mut knock_back: phys::KnockBack = weapon.knock_back.clone().into();
+        knock_back.force_magnitude *= strength;
+        l8r.insert_one(wep_ent, knock_back);
+
+        // each throw gets a fresh chance to hit everything
+        l8r.insert_one(wep_ent, combat::HitRecord::default());
 
         l8r.insert_one(
             wep_ent,
             // the no clear is important for not knocking back things later
             phys::Force::new_no_clear(
-                delta.into_inner() * weapon.force_magnitude,
+                delta.into_inner() * weapon.force_magnitude * strength,
                 weapon.force_decay,
             ),
         );
+        // thrown hard enough, it'd pass right through thin things without this
+        l8r.insert_one(wep_ent, phys::Bullet::default());
     }
 
     Some(())
@@ -0,0 +1,116 @@
+use crate::{
+    combat,
+    phys::{self, PhysHandle},
+    Game,
+};
+
+/// How quickly a returning boomerang turns to face its wielder, in the range [0, 1].
+const STEER: f32 = 0.15;
+
+/// Boomerang weapons curve out and then home back in on whoever threw them.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BoomerangConfig {
+    /// How far the boomerang flies before it turns back around.
+    pub distance: f32,
+    /// How far the boomerang's path bends each frame on its way out, in radians.
+    /// Negative values curve the other way.
+    pub curvature: f32,
+    /// How fast the boomerang flies on its way back.
+    pub return_speed: f32,
+    /// How close the boomerang needs to come to its wielder to be caught.
+    pub catch_radius: f32,
+}
+impl BoomerangConfig {
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.label("distance");
+        ui.add(egui::DragValue::f32(&mut self.distance).speed(0.01));
+
+        ui.add(egui::Slider::f32(&mut self.curvature, -0.2..=0.2).text("curvature"));
+
+        ui.label("return speed");
+        ui.add(egui::DragValue::f32(&mut self.return_speed).speed(0.001));
+
+        ui.label("catch radius");
+        ui.add(egui::DragValue::f32(&mut self.catch_radius).speed(0.01));
+
+        *self != before
+    }
+}
+
+/// A Component on boomerangs that are in the air.
+pub struct Flight {
+    origin: na::Vector2<f32>,
+    returning: bool,
+}
+impl Flight {
+    pub fn new(origin: na::Vector2<f32>) -> Self {
+        Self {
+            origin,
+            returning: false,
+        }
+    }
+}
+
+/// Steers the player's boomerang out along its curve and then back to them,
+/// handing it back once it gets close enough to be caught.
+pub fn boomerang(game: &mut Game) -> Option<()> {
+    let Game {
+        ecs,
+        phys,
+        player,
+        config,
+        ..
+    } = game;
+    let boomerang = config
+        .player
+        .weapon(player.weapon_index)?
+        .boomerang
+        .as_ref()?;
+    let wep_ent = player.thrown.as_ref()?.entity;
+
+    let wielder_loc = phys
+        .collision_object(player.phys_handle)?
+        .position()
+        .translation
+        .vector;
+    let wep_loc = phys
+        .collision_object(*ecs.get::<PhysHandle>(wep_ent).ok()?)?
+        .position()
+        .translation
+        .vector;
+
+    let mut flight = ecs.get_mut::<Flight>(wep_ent).ok()?;
+    let mut force = ecs.get_mut::<phys::Force>(wep_ent).ok()?;
+
+    if !flight.returning {
+        let out_of_steam = force.vec.magnitude() < boomerang.return_speed;
+        if out_of_steam || (wep_loc - flight.origin).magnitude() >= boomerang.distance {
+            flight.returning = true;
+
+            // it gets to hit everything again on the way back
+            if let Ok(mut record) = ecs.get_mut::<combat::HitRecord>(wep_ent) {
+                *record = Default::default();
+            }
+        } else {
+            force.vec = na::UnitComplex::new(boomerang.curvature).transform_vector(&force.vec);
+            return Some(());
+        }
+    }
+
+    let to_wielder = wielder_loc - wep_loc;
+    if to_wielder.magnitude() < boomerang.catch_radius {
+        drop((flight, force));
+        super::recover_weapon(game, wep_ent);
+        return Some(());
+    }
+
+    let desired = to_wielder.normalize() * boomerang.return_speed;
+    force.vec += (desired - force.vec) * STEER;
+
+    Some(())
+}
@@ -0,0 +1,138 @@
+/// How the charge builds up over time.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ChargeCurve {
+    Linear,
+    /// Slow to start, quick to finish.
+    EaseIn,
+    /// Quick to start, slow to finish.
+    EaseOut,
+}
+#[cfg(feature = "confui")]
+const ALL_CHARGE_CURVES: &[ChargeCurve] = &[
+    ChargeCurve::Linear,
+    ChargeCurve::EaseIn,
+    ChargeCurve::EaseOut,
+];
+impl ChargeCurve {
+    /// Takes and returns a value in the range [0, 1].
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            ChargeCurve::Linear => t,
+            ChargeCurve::EaseIn => t * t,
+            ChargeCurve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// Holding onto a weapon too long after it's fully charged weakens the throw.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Overcharge {
+    /// How many frames the weapon can be held at full charge before it's overcharged.
+    pub grace: u16,
+    /// Replaces the strength of an overcharged throw.
+    pub multiplier: f32,
+}
+
+/// Holding a weapon past Readied builds up charge,
+/// which scales the force, damage and knock back of the throw.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChargeConfig {
+    /// How many frames it takes to reach full charge.
+    pub time: u16,
+    pub curve: ChargeCurve,
+    /// The strength of a throw released as soon as the weapon is Readied.
+    pub minimum: f32,
+    /// The strength of a fully charged throw.
+    pub maximum: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overcharge: Option<Overcharge>,
+}
+impl ChargeConfig {
+    /// How far along the charge is after `frames`, in the range [0, 1].
+    pub fn fraction(&self, frames: u16) -> f32 {
+        (frames as f32 / self.time.max(1) as f32).min(1.0)
+    }
+
+    pub fn overcharged(&self, frames: u16) -> bool {
+        self.overcharge
+            .map_or(false, |o| frames > self.time.saturating_add(o.grace))
+    }
+
+    /// What the throw's force, damage and knock back are multiplied by after `frames` of charging.
+    pub fn multiplier(&self, frames: u16) -> f32 {
+        match self.overcharge {
+            Some(o) if self.overcharged(frames) => o.multiplier,
+            _ => {
+                let t = self.curve.apply(self.fraction(frames));
+                self.minimum + (self.maximum - self.minimum) * t
+            }
+        }
+    }
+
+    #[cfg(feature = "confui")]
+    /// Returns `true` if "dirty" i.e. meaningful outward-facing changes to the data occured.
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.label("charge time");
+        let mut ct = self.time as f32;
+        ui.add(egui::DragValue::f32(&mut ct));
+        self.time = ct.max(1.0).round() as u16;
+
+        ui.collapsing("curve", |ui| {
+            for &c in ALL_CHARGE_CURVES {
+                ui.radio_value(format!("{:?}", c), &mut self.curve, c);
+            }
+        });
+
+        ui.label("minimum strength");
+        ui.add(egui::DragValue::f32(&mut self.minimum).speed(0.01));
+        ui.label("maximum strength");
+        ui.add(egui::DragValue::f32(&mut self.maximum).speed(0.01));
+
+        let mut overcharge = self.overcharge.is_some();
+        ui.checkbox("overcharge", &mut overcharge)
+            .tooltip_text("does holding a full charge for too long weaken the throw?");
+        self.overcharge = if overcharge {
+            let mut o = self.overcharge.unwrap_or(Overcharge {
+                grace: 60,
+                multiplier: 0.5,
+            });
+            ui.label("grace frames");
+            let mut grace = o.grace as f32;
+            ui.add(egui::DragValue::f32(&mut grace));
+            o.grace = grace.max(0.0).round() as u16;
+            ui.label("overcharged strength");
+            ui.add(egui::DragValue::f32(&mut o.multiplier).speed(0.01));
+            Some(o)
+        } else {
+            None
+        };
+
+        *self != before
+    }
+}
+
+#[test]
+fn multiplier() {
+    let mut charge = ChargeConfig {
+        time: 10,
+        curve: ChargeCurve::Linear,
+        minimum: 1.0,
+        maximum: 2.0,
+        overcharge: None,
+    };
+    assert_eq!(charge.multiplier(0), 1.0);
+    assert_eq!(charge.multiplier(5), 1.5);
+    assert_eq!(charge.multiplier(10), 2.0);
+    assert_eq!(charge.multiplier(500), 2.0);
+
+    charge.overcharge = Some(Overcharge {
+        grace: 5,
+        multiplier: 0.5,
+    });
+    assert_eq!(charge.multiplier(15), 2.0);
+    assert_eq!(charge.multiplier(16), 0.5);
+}
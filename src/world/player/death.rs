@@ -0,0 +1,62 @@
+use super::PlayerState;
+use crate::{combat, draw, Game};
+
+/// Notices when the player runs out of Health and plays their death animation,
+/// then brings them back at their checkpoint with all of the instances reset.
+pub fn death(
+    Game {
+        ecs,
+        phys,
+        player,
+        config,
+        dead,
+        instance_tracker,
+        encounters,
+        stats,
+        ..
+    }: &mut Game,
+) -> Option<()> {
+    let player_config = &config.player;
+
+    match player.state {
+        PlayerState::Dead { timer } if timer >= player_config.respawn_time => {
+            *ecs.get_mut::<combat::Health>(player.entity).ok()? =
+                combat::Health::new(player_config.health);
+            drop(ecs.remove_one::<draw::Playback>(player.entity));
+            ecs.get_mut::<draw::Looks>(player.entity).ok()?.art = player_config.directions.down.art;
+
+            phys.get_mut(player.phys_handle)?
+                .set_position(na::Isometry2::new(player.checkpoint, 0.0));
+
+            player.walk_animator = Default::default();
+            player.state = PlayerState::Walking;
+
+            instance_tracker.start_reset(dead);
+            encounters.reset();
+            *stats = Default::default();
+        }
+        PlayerState::Dead { timer } => player.state = PlayerState::Dead { timer: timer + 1 },
+        _ => {
+            if ecs.get::<combat::Health>(player.entity).ok()?.is_alive() {
+                return Some(());
+            }
+            player.state = PlayerState::Dead { timer: 0 };
+
+            // a life is as long as a run of stats lasts
+            stats.land();
+            #[cfg(not(target_arch = "wasm32"))]
+            stats.save();
+
+            if let Some(art) = player_config.death_animation {
+                ecs.get_mut::<draw::Looks>(player.entity).ok()?.art = art;
+                ecs.get_mut::<draw::AnimationFrame>(player.entity).ok()?.0 = 0;
+
+                let mut playback = draw::Playback::default();
+                playback.looping = false;
+                drop(ecs.insert_one(player.entity, playback));
+            }
+        }
+    }
+
+    Some(())
+}
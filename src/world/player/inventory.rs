@@ -0,0 +1,60 @@
+use crate::Game;
+use macroquad::*;
+
+const NUMBER_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Swaps out the player's weapon when a number key is pressed or the mouse wheel is scrolled.
+///
+/// Weapons can only be switched while they're in hand and not being readied.
+/// The number keys are only listened to if `keys` is set, and the wheel only if `wheel` is.
+pub fn switch_weapons(
+    Game {
+        ecs,
+        dead,
+        player,
+        config,
+        ..
+    }: &mut Game,
+    keys: bool,
+    wheel: bool,
+) -> Option<()> {
+    let weapon_count = config.player.weapons.len();
+    let current = player.weapon_index;
+    if weapon_count == 0 {
+        return None;
+    }
+
+    let (_, wheel) = if wheel { mouse_wheel() } else { (0.0, 0.0) };
+    let desired = NUMBER_KEYS
+        .iter()
+        .take(weapon_count)
+        .position(|&k| keys && is_key_pressed(k))
+        .or_else(|| match wheel {
+            w if w > 0.0 => Some((current + 1) % weapon_count),
+            w if w < 0.0 => Some((current + weapon_count - 1) % weapon_count),
+            _ => None,
+        })
+        .filter(|&i| i != current)?;
+
+    if player.thrown.is_some() || !player.wielder.idle() {
+        return None;
+    }
+
+    let old = player.weapon_entity.take()?;
+    dead.mark(old);
+
+    player.weapon_index = desired;
+    player.weapon_entity = Some(config.player.weapon(desired)?.spawn(ecs));
+
+    Some(())
+}
@@ -0,0 +1,172 @@
+use super::{aiming::Keyframe, Direction, PlayerState};
+use crate::{
+    combat, draw,
+    phys::{self, PhysHandle},
+    Game,
+};
+use macroquad::*;
+
+/// A close range swing, for when there's no time to ready a throw.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MeleeConfig {
+    /// Played on the player once per swing.
+    art: draw::ArtHandle,
+    /// How many frames the hitbox is out for.
+    duration: u16,
+    /// How many frames after a swing ends before another one can begin.
+    cooldown: u16,
+    hitbox: phys::Hitbox,
+    hurtful: combat::Hurtful,
+    knock_back: phys::KnockBackConfig,
+    /// Where the hitbox is over the course of the swing, relative to the player,
+    /// as if they were facing right.
+    keyframes: Vec<Keyframe>,
+}
+impl MeleeConfig {
+    /// Where the hitbox should be `timer` frames into the swing, relative to the player.
+    fn hitbox_iso(&self, timer: u16, facing: f32) -> Option<na::Isometry2<f32>> {
+        let last = self.keyframes.last()?;
+        let prog = timer as f32 / self.duration.max(1) as f32;
+        let frame = Keyframe::interpolate(&self.keyframes, prog, last);
+        Some(na::Isometry2::rotation(facing) * frame.into_iso2())
+    }
+
+    #[cfg(feature = "confui")]
+    pub fn dev_ui(&mut self, ui: &mut egui::Ui, draw: &mut draw::Config) {
+        ui.collapsing("Art", |ui| draw.select_handle_dev_ui(ui, &mut self.art));
+
+        ui.collapsing("Timing", |ui| {
+            ui.label("duration");
+            let mut d = self.duration as f32;
+            ui.add(egui::DragValue::f32(&mut d));
+            self.duration = d.max(1.0).round() as u16;
+
+            ui.label("cooldown");
+            let mut c = self.cooldown as f32;
+            ui.add(egui::DragValue::f32(&mut c));
+            self.cooldown = c.max(0.0).round() as u16;
+        });
+
+        ui.collapsing("Damage", |ui| {
+            ui.collapsing("Hitbox", |ui| self.hitbox.dev_ui(ui));
+            ui.collapsing("Hurtful", |ui| self.hurtful.dev_ui(ui));
+            ui.collapsing("Knock Back", |ui| self.knock_back.dev_ui(ui));
+        });
+
+        ui.collapsing("Keyframes", |ui| {
+            super::aiming::keyframes_dev_ui(ui, &mut self.keyframes)
+        });
+    }
+}
+
+struct Swing {
+    timer: u16,
+    hitbox: hecs::Entity,
+    /// The angle the player was facing when the swing began.
+    facing: f32,
+}
+
+#[derive(Default)]
+pub struct Melee {
+    swing: Option<Swing>,
+    cooldown: u16,
+}
+impl Melee {
+    /// The hitbox of the swing in progress, if there is one.
+    pub fn hitbox(&self) -> Option<hecs::Entity> {
+        self.swing.as_ref().map(|s| s.hitbox)
+    }
+}
+
+/// Right clicking swings at whatever's in front of the player,
+/// so long as they aren't busy with their weapon.
+pub fn melee(game: &mut Game, start_swing: bool) -> Option<()> {
+    let Game {
+        ecs,
+        phys,
+        dead,
+        l8r,
+        player,
+        config,
+        ..
+    } = game;
+    let melee_config = config.player.melee.as_ref()?;
+    let player_loc = phys
+        .collision_object(player.phys_handle)?
+        .position()
+        .translation
+        .vector;
+    let melee = &mut player.melee;
+
+    let swing = match melee.swing.as_mut() {
+        Some(swing) => swing,
+        None => {
+            melee.cooldown = melee.cooldown.saturating_sub(1);
+
+            if !start_swing
+                || melee.cooldown > 0
+                || !is_mouse_button_pressed(MouseButton::Right)
+                || player.state.is_throwing()
+                || !player.wielder.idle()
+            {
+                return Some(());
+            }
+
+            let facing = match player.walk_animator.direction {
+                Direction::Up => -std::f32::consts::FRAC_PI_2,
+                Direction::Down => std::f32::consts::FRAC_PI_2,
+                Direction::Side if ecs.get::<draw::Looks>(player.entity).ok()?.flip_x => {
+                    std::f32::consts::PI
+                }
+                Direction::Side => 0.0,
+            };
+
+            let mut iso = melee_config.hitbox_iso(0, facing)?;
+            iso.translation.vector += player_loc;
+            let shape = melee_config.hitbox.shape()?;
+            let hitbox = ecs.spawn((
+                melee_config.hurtful.clone(),
+                combat::HitRecord::default(),
+                Into::<phys::KnockBack>::into(melee_config.knock_back.clone()),
+            ));
+            let groups = phys::collision_groups(Some(&["Weapon"]), Some(&["Enemy"]), &[]);
+            l8r.l8r(move |game| drop(game.make_physical(hitbox, iso, shape, groups)));
+
+            ecs.get_mut::<draw::Looks>(player.entity).ok()?.art = melee_config.art;
+            ecs.get_mut::<draw::AnimationFrame>(player.entity).ok()?.0 = 0;
+            let mut playback = draw::Playback::default();
+            playback.looping = false;
+            drop(ecs.insert_one(player.entity, playback));
+
+            player.state = PlayerState::Swinging;
+            melee.swing = Some(Swing {
+                timer: 0,
+                hitbox,
+                facing,
+            });
+            return Some(());
+        }
+    };
+
+    swing.timer += 1;
+    if swing.timer >= melee_config.duration || !player.state.is_swinging() {
+        dead.mark(swing.hitbox);
+        melee.swing = None;
+        melee.cooldown = melee_config.cooldown;
+
+        // if they were interrupted, e.g. by dying, whatever interrupted them is in control now
+        if player.state.is_swinging() {
+            drop(ecs.remove_one::<draw::Playback>(player.entity));
+            player.state = PlayerState::Walking;
+        }
+        return Some(());
+    }
+
+    let mut iso = melee_config.hitbox_iso(swing.timer, swing.facing)?;
+    iso.translation.vector += player_loc;
+    let h = *ecs.get::<PhysHandle>(swing.hitbox).ok()?;
+    phys.get_mut(h)?.set_position(iso);
+
+    Some(())
+}
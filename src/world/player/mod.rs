@@ -2,17 +2,35 @@ mod aiming;
 pub use aiming::aiming;
 mod movement;
 pub use movement::movement;
+mod death;
+pub use death::death;
+mod recovery;
+pub use recovery::{recover_weapon, recovery};
+mod inventory;
+pub use inventory::switch_weapons;
+mod boomerang;
+mod charge;
+pub use boomerang::boomerang;
+mod melee;
+mod trajectory;
+pub use melee::melee;
 
 use crate::{
     combat, draw,
-    phys::{self, Collide, CollisionGroups, Cuboid, PhysHandle},
+    phys::{self, Cuboid, PhysHandle, ShapeHandle},
 };
 
 #[derive(Copy, Clone, Debug)]
 pub enum PlayerState {
     Walking,
     Throwing,
+    /// In the middle of a melee swing.
+    Swinging,
     Standing,
+    /// Out of Health; counts up until it's time to respawn.
+    Dead {
+        timer: u16,
+    },
 }
 impl PlayerState {
     pub fn is_walking(self) -> bool {
@@ -23,9 +41,17 @@ impl PlayerState {
         matches!(self, PlayerState::Throwing)
     }
 
+    pub fn is_swinging(self) -> bool {
+        matches!(self, PlayerState::Swinging)
+    }
+
     pub fn is_standing(self) -> bool {
         matches!(self, PlayerState::Standing)
     }
+
+    pub fn is_dead(self) -> bool {
+        matches!(self, PlayerState::Dead { .. })
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -67,20 +93,93 @@ struct DirectionConfig {
 #[serde(deny_unknown_fields)]
 pub struct Config {
     directions: EachDirection<DirectionConfig>,
-    weapon: aiming::WeaponConfig,
+    /// The player can switch between these with the number keys or the scroll wheel.
+    weapons: Vec<aiming::WeaponConfig>,
+    /// If set, right clicking swings at whatever's in front of the player.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    melee: Option<melee::MeleeConfig>,
     speed: f32,
     stop_decay: f32,
+    health: usize,
+    /// How hard the player is to shove around; left out, the player weighs as much as anything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mass: Option<phys::collision::Mass>,
+    /// Played once when the player runs out of Health.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    death_animation: Option<draw::ArtHandle>,
+    /// How many frames pass after the player dies before they respawn.
+    respawn_time: u16,
 }
 impl Config {
+    fn weapon(&self, index: usize) -> Option<&aiming::WeaponConfig> {
+        self.weapons.get(index)
+    }
+
     #[cfg(feature = "confui")]
-    pub fn dev_ui(&mut self, ui: &mut egui::Ui) {
+    /// Returns the weapons as they were before, if any of them were changed, added or removed.
+    pub fn dev_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        draw: &mut draw::Config,
+    ) -> Option<Vec<aiming::WeaponConfig>> {
+        let weapons_before = self.weapons.clone();
+
         ui.collapsing("Speed", |ui| {
             ui.label("speed");
             ui.add(egui::DragValue::f32(&mut self.speed).speed(0.005));
             ui.label("stop walk slowdown decay");
             ui.add(egui::DragValue::f32(&mut self.stop_decay).speed(0.005));
         });
-        ui.collapsing("Weapon", |ui| self.weapon.dev_ui(ui));
+        ui.collapsing("Health", |ui| {
+            ui.label("health");
+            let mut hp = self.health as f32;
+            ui.add(egui::DragValue::f32(&mut hp));
+            self.health = hp.max(1.0) as usize;
+
+            ui.label("respawn time");
+            let mut rt = self.respawn_time as f32;
+            ui.add(egui::DragValue::f32(&mut rt));
+            self.respawn_time = rt.round() as u16;
+        });
+        ui.collapsing("Mass", |ui| {
+            let mut has_mass = self.mass.is_some();
+            ui.checkbox("custom mass", &mut has_mass);
+            match (has_mass, &mut self.mass) {
+                (true, Some(mass)) => {
+                    mass.dev_ui(ui);
+                }
+                (true, None) => self.mass = Some(Default::default()),
+                (false, _) => self.mass = None,
+            }
+        });
+        ui.collapsing("Weapons", |ui| {
+            let weapon_count = self.weapons.len();
+            let mut removal_index: Option<usize> = None;
+            for (i, weapon) in self.weapons.iter_mut().enumerate() {
+                ui.collapsing(format!("{}: {}", i + 1, weapon.name), |ui| {
+                    weapon.dev_ui(ui, draw);
+                    if weapon_count > 1 && ui.button("Remove Weapon").clicked {
+                        removal_index = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removal_index {
+                self.weapons.remove(i);
+            }
+
+            if ui.button("Add Weapon").clicked {
+                if let Some(last) = self.weapons.last() {
+                    let mut new = last.clone();
+                    new.name = format!("{} copy", new.name);
+                    self.weapons.push(new);
+                }
+            }
+        });
+        if let Some(melee) = &mut self.melee {
+            ui.collapsing("Melee", |ui| melee.dev_ui(ui, draw));
+        }
+
+        Some(weapons_before).filter(|before| self.weapons != *before)
     }
 }
 
@@ -89,8 +188,17 @@ pub struct Player {
     pub entity: hecs::Entity,
     pub phys_handle: PhysHandle,
     pub weapon_entity: Option<hecs::Entity>,
+    /// Which of the Config's weapons the player is using.
+    pub weapon_index: usize,
+    /// The weapon that's been thrown, if it hasn't been picked back up yet.
+    pub thrown: Option<recovery::Thrown>,
+    /// Where the weapon is predicted to go, while a throw is being readied.
+    pub trajectory: Option<trajectory::Trajectory>,
     pub wielder: aiming::Wielder,
+    pub melee: melee::Melee,
     pub walk_animator: movement::WalkAnimator,
+    /// Where the player comes back to life after dying.
+    pub checkpoint: na::Vector2<f32>,
 }
 impl Player {
     pub fn new(
@@ -98,32 +206,14 @@ impl Player {
         phys: &mut phys::CollisionWorld,
         config: &super::Config,
     ) -> Self {
-        let wep_ent = ecs.spawn((
-            draw::Looks::art(config.draw.art("baseball.png")),
-            combat::Hurtful {
-                minimum_speed: 0.05,
-                raw_damage: 1.0,
-                minimum_damage: 1,
-                kind: combat::HurtfulKind::Ram {
-                    speed_damage_coefficient: 1.0,
-                },
-            },
-            phys::KnockBack {
-                groups: CollisionGroups::new()
-                    .with_membership(&[Collide::Weapon as usize])
-                    .with_whitelist(&[Collide::Enemy as usize]),
-                force_decay: 0.75,
-                force_magnitude: 0.75,
-                use_force_direction: true,
-                // TODO: separate minimum_speed_to_knock_back
-                minimum_speed: Some(0.05),
-            },
-        ));
-
         let ent = ecs.spawn((
             draw::Looks::art(config.player.directions.down.art),
             draw::AnimationFrame(3),
+            combat::Health::new(config.player.health),
         ));
+        if let Some(mass) = config.player.mass {
+            ecs.insert_one(ent, mass).unwrap();
+        }
         Player {
             entity: ent,
             state: PlayerState::Walking,
@@ -133,11 +223,69 @@ impl Player {
                 phys,
                 ent,
                 na::Isometry::identity(),
-                Cuboid::new(na::Vector2::new(0.6, 0.15) / 2.0),
-                CollisionGroups::new().with_membership(&[phys::Collide::Player as usize]),
+                ShapeHandle::new(Cuboid::new(na::Vector2::new(0.6, 0.15) / 2.0)),
+                phys::collision_groups(Some(&["Player"]), None, &[]),
             ),
-            weapon_entity: Some(wep_ent),
+            weapon_entity: config.player.weapon(0).map(|weapon| weapon.spawn(ecs)),
+            weapon_index: 0,
+            thrown: None,
+            trajectory: None,
             wielder: aiming::Wielder::new(),
+            melee: Default::default(),
+            checkpoint: na::zero(),
+        }
+    }
+
+    /// Keeps `weapon_index` pointing at the same weapon after the Config's weapons are edited,
+    /// following it by name if it moved, and swapping out the weapon in hand
+    /// if the config it came from changed or is gone.
+    #[cfg(feature = "confui")]
+    pub fn fit_weapon_index(
+        &mut self,
+        ecs: &mut hecs::World,
+        dead: &mut super::Dead,
+        config: &Config,
+        weapons_before: &[aiming::WeaponConfig],
+    ) {
+        let held = match weapons_before.get(self.weapon_index) {
+            Some(held) => held,
+            None => return,
+        };
+        let last = match config.weapons.len().checked_sub(1) {
+            Some(last) => last,
+            None => return,
+        };
+
+        self.weapon_index = config
+            .weapons
+            .iter()
+            .position(|w| w.name == held.name)
+            .unwrap_or(self.weapon_index.min(last));
+        if config.weapon(self.weapon_index) == Some(held) {
+            return;
+        }
+
+        if let Some(old) = self.weapon_entity.take() {
+            dead.mark(old);
+            self.weapon_entity = config
+                .weapon(self.weapon_index)
+                .map(|weapon| weapon.spawn(ecs));
         }
     }
+
+    /// Whether `e` is one of the player's weapons, thrown, in hand or mid swing.
+    pub fn wields(&self, e: hecs::Entity) -> bool {
+        self.weapon_entity == Some(e)
+            || self.thrown.as_ref().map(|t| t.entity) == Some(e)
+            || self.melee.hitbox() == Some(e)
+    }
+
+    /// How charged up the throw being held is, in the range [0, 1],
+    /// and whether or not it's been held for too long.
+    /// None if the weapon isn't Readied or can't be charged.
+    pub fn charge(&self, config: &Config) -> Option<(f32, bool)> {
+        let charge = config.weapon(self.weapon_index)?.charge.as_ref()?;
+        let frames = self.wielder.charge_frames()?;
+        Some((charge.fraction(frames), charge.overcharged(frames)))
+    }
 }
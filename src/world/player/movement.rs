@@ -1,5 +1,6 @@
 use super::Direction;
 use crate::{
+    combat::StatusEffects,
     draw::{AnimationFrame, Looks},
     Game,
 };
@@ -32,6 +33,9 @@ pub fn movement(
         .query_one::<(&mut AnimationFrame, &mut Looks)>(player.entity)
         .ok()?;
     let (af, looks) = query.get()?;
+    let slow = ecs
+        .get::<StatusEffects>(player.entity)
+        .map_or(1.0, |fx| fx.slow_factor());
 
     #[rustfmt::skip]
     let keymap = [
@@ -48,7 +52,7 @@ pub fn movement(
         .normalize();
 
     let vel = if move_vec.magnitude_squared() > 0.0 {
-        let vel = move_vec * config.player.speed;
+        let vel = move_vec * config.player.speed * slow;
         player.walk_animator.last_move = vel;
 
         let new_direction = match (vel.x.abs() > std::f32::EPSILON, vel.y < 0.0) {
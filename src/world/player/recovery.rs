@@ -0,0 +1,96 @@
+use super::aiming::Wielder;
+use crate::{
+    phys::{self, PhysHandle},
+    world, Game,
+};
+
+/// Below this speed, a thrown weapon is considered to be resting on the ground.
+const REST_SPEED: f32 = 0.005;
+
+/// A weapon that's been thrown and not yet picked back up.
+pub struct Thrown {
+    pub entity: hecs::Entity,
+    frames: u16,
+}
+impl Thrown {
+    pub fn new(entity: hecs::Entity) -> Self {
+        Self { entity, frames: 0 }
+    }
+}
+
+/// Once a thrown weapon comes to rest, the player can walk over to it to pick it up,
+/// although scripts get the final say on that via `weapon-pickup?`.
+/// Weapons can also be configured to come back on their own after a while.
+pub fn recovery(game: &mut Game) -> Option<()> {
+    let Game {
+        ecs,
+        phys,
+        player,
+        config,
+        ..
+    } = game;
+    let weapon = config.player.weapon(player.weapon_index)?;
+    let thrown = player.thrown.as_mut()?;
+    thrown.frames = thrown.frames.saturating_add(1);
+    let wep_ent = thrown.entity;
+
+    if weapon
+        .auto_return_time
+        .map_or(false, |t| thrown.frames >= t)
+    {
+        recover_weapon(game, wep_ent);
+        return Some(());
+    }
+
+    let resting = ecs
+        .get::<phys::Force>(wep_ent)
+        .map_or(true, |f| f.vec.magnitude() < REST_SPEED);
+    if !resting {
+        return Some(());
+    }
+
+    let wep_loc = phys
+        .collision_object(*ecs.get::<PhysHandle>(wep_ent).ok()?)?
+        .position()
+        .translation
+        .vector;
+    let player_loc = phys
+        .collision_object(player.phys_handle)?
+        .position()
+        .translation
+        .vector;
+    if (wep_loc - player_loc).magnitude() < weapon.pickup_radius {
+        glsp::lib_mut::<world::script::Cache>()
+            .weapon_pickups
+            .push(wep_ent);
+    }
+
+    Some(())
+}
+
+/// Hands the thrown weapon back to the player, ready to be thrown again.
+pub fn recover_weapon(game: &mut Game, wep_ent: hecs::Entity) {
+    if !game
+        .player
+        .thrown
+        .as_ref()
+        .map_or(false, |t| t.entity == wep_ent)
+    {
+        return;
+    }
+
+    game.stats.land();
+
+    let player = &mut game.player;
+    player.thrown = None;
+    player.weapon_entity = Some(wep_ent);
+    player.wielder = Wielder::new();
+
+    drop(game.ecs.remove_one::<phys::Force>(wep_ent));
+    drop(game.ecs.remove_one::<phys::Bullet>(wep_ent));
+    drop(game.ecs.remove_one::<super::boomerang::Flight>(wep_ent));
+    // aiming gives it a new body with the groups it should have before being thrown
+    if let Ok(&h) = game.ecs.get::<PhysHandle>(wep_ent).as_deref() {
+        game.remove_physical(wep_ent, h);
+    }
+}
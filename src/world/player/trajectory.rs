@@ -0,0 +1,90 @@
+use super::boomerang::BoomerangConfig;
+use crate::phys;
+use ncollide2d::pipeline::GeometricQueryType;
+
+/// Throws that take longer than this many frames to stop aren't predicted any further.
+const MAX_FRAMES: usize = 120;
+/// Below this speed, a thrown weapon is predicted to have stopped.
+const STOP_SPEED: f32 = 0.005;
+
+/// Where a throw would end up if it ran into something on the way.
+#[derive(Debug, Copy, Clone)]
+pub struct Stop {
+    pub pos: na::Vector2<f32>,
+    /// Whether or not the thing it would run into is an Enemy.
+    pub enemy: bool,
+}
+
+/// Where a weapon is predicted to go if it were thrown right now.
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+    /// Where the weapon would be on each frame of its flight.
+    pub points: Vec<na::Vector2<f32>>,
+    pub stop: Option<Stop>,
+}
+
+/// Simulates a Force of `velocity` decaying by `decay` each frame,
+/// cutting the path short at the first thing in `groups` that `shape` would run into.
+///
+/// Boomerangs are only followed along their curve out, up until they'd turn back around.
+pub(super) fn predict(
+    phys: &phys::CollisionWorld,
+    start: na::Isometry2<f32>,
+    velocity: na::Vector2<f32>,
+    decay: f32,
+    boomerang: Option<&BoomerangConfig>,
+    shape: &phys::ShapeHandle<f32>,
+    groups: &phys::CollisionGroups,
+) -> Trajectory {
+    let origin = start.translation.vector;
+    let (curvature, range, stop_speed) = match boomerang {
+        Some(b) => (b.curvature, b.distance, b.return_speed.max(STOP_SPEED)),
+        None => (0.0, std::f32::INFINITY, STOP_SPEED),
+    };
+
+    let enemy_group = glsp::lib::<phys::CollideTable>().index("Enemy");
+
+    let mut points = Vec::with_capacity(MAX_FRAMES);
+    let mut iso = start;
+    let mut vel = velocity;
+    while points.len() < MAX_FRAMES {
+        let speed = vel.magnitude();
+        if speed < stop_speed || (iso.translation.vector - origin).magnitude() >= range {
+            break;
+        }
+
+        let direction = na::Unit::new_unchecked(vel / speed);
+        let obstacle = phys
+            .sweep_test(&**shape, &iso, &direction, speed, groups)
+            // Triggers don't stop anything
+            .filter(|(h, _)| {
+                phys.collision_object(*h).map_or(false, |o| {
+                    matches!(o.query_type(), GeometricQueryType::Contacts(..))
+                })
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.toi
+                    .partial_cmp(&b.toi)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        if let Some((h, toi)) = obstacle {
+            let stop = Stop {
+                pos: iso.translation.vector + direction.into_inner() * toi.toi,
+                enemy: match (phys.collision_object(h), enemy_group) {
+                    (Some(obj), Some(enemy)) => obj.collision_groups().is_member_of(enemy),
+                    _ => false,
+                },
+            };
+            return Trajectory {
+                points,
+                stop: Some(stop),
+            };
+        }
+
+        iso.translation.vector += vel;
+        points.push(iso.translation.vector);
+        vel = na::UnitComplex::new(curvature).transform_vector(&(vel * decay));
+    }
+
+    Trajectory { points, stop: None }
+}
@@ -21,6 +21,7 @@ pub(super) fn spawn_comps(
     let mut tags = vec![];
     let mut art = None;
     let mut z_offset = None;
+    let mut triggers = vec![];
 
     for comp in prefab {
         match comp {
@@ -33,7 +34,30 @@ pub(super) fn spawn_comps(
             Health(amount) => {
                 b.add(combat::Health::new(amount));
             }
+            Resistances(r) => {
+                b.add(r);
+            }
+            HealthBar(bar) => {
+                b.add(bar);
+            }
+            Hurtful(hurtful) => {
+                // without a HitRecord, a rehit interval wouldn't mean anything
+                if hurtful.rehit_interval.is_some() {
+                    b.add(combat::HitRecord::default());
+                }
+                b.add(hurtful);
+            }
+            KnockBack(kb) => {
+                b.add::<phys::KnockBack>(kb.into());
+            }
+            Mass(mass) => {
+                b.add(mass);
+            }
+            Velocity(vel) => {
+                b.add(vel);
+            }
             Position(_) | Angle(_) | Collision(_) | Hitbox(_) => pm.apply_comp(&comp),
+            Trigger(trigger) => triggers.push(trigger),
             Script(name) => script_name = Some(name),
         }
     }
@@ -51,7 +75,21 @@ pub(super) fn spawn_comps(
 
     let e = ecs.spawn(b.build());
 
+    // Triggers go where the hitbox would, whether or not there's a hitbox
+    let iso = pm
+        .position
+        .map(|p| na::Isometry2::new(p, pm.angle.unwrap_or(0.0)));
     let _ = pm.build(ecs, phys, e);
+    for trigger in &triggers {
+        match iso {
+            Some(iso) => {
+                if phys::trigger_insert(ecs, phys, e, iso, trigger).is_none() {
+                    glsp::eprn!("Couldn't add Trigger, its shape isn't valid");
+                }
+            }
+            None => glsp::eprn!("Couldn't add Trigger without a Position"),
+        }
+    }
 
     if let Some(name) = script_name {
         glsp::lib_mut::<world::script::Intake>()
@@ -69,7 +107,7 @@ struct PhysMake {
     position: Option<na::Vector2<f32>>,
     angle: Option<f32>,
     collision: Option<phys::Collisionship>,
-    hitbox: Option<na::Vector2<f32>>,
+    hitbox: Option<phys::Hitbox>,
 }
 impl PhysMake {
     fn apply_comp(&mut self, comp: &Comp) {
@@ -78,7 +116,7 @@ impl PhysMake {
             &Position(p) => self.position = Some(p),
             &Angle(a) => self.angle = Some(a),
             Collision(c) => self.collision = Some(c.clone()),
-            &Hitbox(hb) => self.hitbox = Some(hb),
+            Hitbox(hb) => self.hitbox = Some(hb.clone()),
             _ => {}
         }
     }
@@ -91,7 +129,11 @@ impl PhysMake {
     ) -> Result<PhysHandle, &'static str> {
         let pos = self.position.ok_or_else(|| "No Position")?;
         let coll = self.collision.ok_or_else(|| "No Collision Mask")?;
-        let hb = self.hitbox.ok_or_else(|| "No Hitbox")?;
+        let shape = self
+            .hitbox
+            .ok_or_else(|| "No Hitbox")?
+            .shape()
+            .ok_or_else(|| "Hitbox isn't a valid shape")?;
         let angle = self.angle.unwrap_or(0.0);
         let (c_static, groups) = coll.into();
 
@@ -106,7 +148,7 @@ impl PhysMake {
             phys,
             e,
             na::Isometry2::new(pos, angle),
-            phys::Cuboid::new(hb / 2.0),
+            shape,
             groups,
         ))
     }
@@ -149,10 +191,17 @@ pub enum Comp {
     DeathAnimation(draw::ArtHandle),
     Tags(Vec<Tag>),
     Health(usize),
+    Resistances(combat::Resistances),
+    HealthBar(draw::HealthBar),
+    Hurtful(combat::Hurtful),
+    KnockBack(phys::KnockBackConfig),
+    Mass(phys::collision::Mass),
+    Velocity(phys::Velocity),
     Position(na::Vector2<f32>),
     Angle(f32),
     Collision(phys::Collisionship),
-    Hitbox(na::Vector2<f32>),
+    Hitbox(phys::Hitbox),
+    Trigger(phys::TriggerConfig),
     Script(String),
 }
 #[cfg(feature = "confui")]
@@ -172,10 +221,17 @@ impl Comp {
             DeathAnimation(_) => "Death Animation",
             Tags(_) => "Tags",
             Health(_) => "Health",
+            Resistances(_) => "Resistances",
+            HealthBar(_) => "Health Bar",
+            Hurtful(_) => "Hurtful",
+            KnockBack(_) => "Knock Back",
+            Mass(_) => "Mass",
+            Velocity(_) => "Velocity",
             Position(_) => "Position",
             Angle(_) => "Angle",
             Collision(_) => "Collision",
             Hitbox(_) => "Hitbox",
+            Trigger(_) => "Trigger",
             Script(_) => "Script",
         }
     }
@@ -202,6 +258,12 @@ impl Comp {
                 }
                 *hp_u = hp as usize;
             }
+            Resistances(r) => return r.dev_ui(ui),
+            HealthBar(bar) => return bar.dev_ui(ui),
+            Hurtful(hurtful) => return hurtful.dev_ui(ui),
+            KnockBack(kb) => return kb.dev_ui(ui),
+            Mass(mass) => return mass.dev_ui(ui),
+            Velocity(vel) => return vel.dev_ui(ui),
             Tags(tags) => {
                 let mut i = 0;
                 tags.drain_filter(|Tag { tag, val }| {
@@ -256,16 +318,8 @@ impl Comp {
                 }
             }
             Collision(col) => return col.dev_ui(ui),
-            Hitbox(hb) => {
-                ui.horizontal(|ui| {
-                    let phb = *hb;
-                    ui.add(egui::DragValue::f32(&mut hb.x).speed(0.001));
-                    ui.add(egui::DragValue::f32(&mut hb.y).speed(0.001));
-                    if phb != *hb {
-                        dirty = true
-                    }
-                });
-            }
+            Hitbox(hb) => return hb.dev_ui(ui),
+            Trigger(trigger) => return trigger.dev_ui(ui),
             Script(name) => {
                 let before_len = name.len();
                 ui.add(egui::TextEdit::new(name));
@@ -297,10 +351,17 @@ impl Comp {
                 DeathAnimation(draw::ArtHandle::new_unchecked(0)),
                 Tags(vec![]),
                 Health(1),
+                Resistances(combat::Resistances::default()),
+                HealthBar(draw::HealthBar::default()),
+                Hurtful(combat::Hurtful::default()),
+                KnockBack(phys::KnockBackConfig::default()),
+                Mass(phys::collision::Mass::default()),
+                Velocity(phys::Velocity::default()),
                 Position(na::zero()),
                 Angle(0.0),
                 Collision(phys::Collisionship::default()),
-                Hitbox(na::zero()),
+                Hitbox(phys::Hitbox::Cuboid(na::zero())),
+                Trigger(phys::TriggerConfig::default()),
                 Script("IntroSlime".to_string()),
             ]
         };
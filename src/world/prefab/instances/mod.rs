@@ -69,13 +69,13 @@ impl Config {
 #[cfg(feature = "confui")]
 pub fn clear_prefab_instances(
     Game {
-        instance_tracker: tracker::Tracker { spawned, .. },
+        instance_tracker,
         dead,
         ..
     }: &mut Game,
     key: PrefabKey,
 ) {
-    for tag in spawned.drain_filter(|t| t.prefab_key == key) {
+    for tag in instance_tracker.untrack_where(|t| t.prefab_key == key) {
         dead.mark(tag.entity);
     }
 }
@@ -152,15 +152,14 @@ pub fn dev_ui(ui: &mut egui::Ui, world: &mut Game) -> Option<()> {
                         let p_foot = {
                             let obj = phys.collision_object(player.phys_handle)?;
                             let player_pos = obj.position().translation.vector;
-                            let &phys::Cuboid { half_extents, .. } = obj.shape().as_shape()?;
-                            player_pos + y_only(half_extents)
+                            player_pos + y_only(phys::half_extents(&**obj.shape()))
                         };
                         // find the Instance's hitbox dimensions, if any
                         let half_extents = prefab.fabs[*prefab_key]
                             .comps
                             .iter()
                             .find_map(|c| match c {
-                                &Comp::Hitbox(hb) => Some(y_only(hb)),
+                                Comp::Hitbox(hb) => Some(y_only(hb.half_extents() * 2.0)),
                                 _ => None,
                             })
                             .unwrap_or_else(|| na::Vector2::zeros());
@@ -169,16 +168,13 @@ pub fn dev_ui(ui: &mut egui::Ui, world: &mut Game) -> Option<()> {
                         p_foot + half_extents
                     })],
                 });
-                world
-                    .instance_tracker
-                    .spawned
-                    .push(prefab.spawn_config_instance(
-                        ecs,
-                        phys,
-                        tag_bank,
-                        &world.config.draw,
-                        instance_key,
-                    ));
+                world.instance_tracker.track(prefab.spawn_config_instance(
+                    ecs,
+                    phys,
+                    tag_bank,
+                    &world.config.draw,
+                    instance_key,
+                ));
                 world.instance_tracker.popup = Clear;
             }
         }
@@ -200,11 +196,10 @@ pub fn clear_dead(
     }: &mut Game,
 ) {
     #[cfg(feature = "confui")]
-    trk.spawned
-        .drain_filter(|tag| !tag.killed && dead.is_marked(tag.entity));
+    trk.untrack_where(|tag| !tag.killed && dead.is_marked(tag.entity));
 
     #[cfg(not(feature = "confui"))]
-    trk.spawned.drain_filter(|tag| dead.is_marked(tag.entity));
+    trk.untrack_where(|tag| dead.is_marked(tag.entity));
 }
 
 /// Respawns instances of prefabs that are marked "dirty"
@@ -220,6 +215,8 @@ pub fn keep_fresh(
         ..
     }: &mut Game,
 ) {
+    let mut respawned = false;
+
     // find all dirty prefabs
     config
         .prefab
@@ -249,6 +246,7 @@ pub fn keep_fresh(
                             instance_key,
                         );
                         t.generation = pf.generation;
+                        respawned = true;
                         return true;
                     }
                     false
@@ -257,6 +255,25 @@ pub fn keep_fresh(
         .map(|(i, _)| i)
         .next()
         .map(|i| config.prefab.fabs[i].dirty = false);
+
+    // some of the Tags have new entities now
+    if respawned {
+        instance_tracker.reindex();
+    }
+}
+
+/// Finishes any reset started with `Tracker::start_reset`.
+pub fn finish_reset(
+    Game {
+        phys,
+        ecs,
+        instance_tracker,
+        tag_bank,
+        config,
+        ..
+    }: &mut Game,
+) {
+    instance_tracker.finish_reset(ecs, phys, tag_bank, config);
 }
 
 pub fn spawn_all_instances(
@@ -269,7 +286,5 @@ pub fn spawn_all_instances(
         ..
     }: &mut Game,
 ) {
-    instance_tracker
-        .spawned
-        .extend(prefab.spawn_all_config_instances(ecs, phys, tag_bank, draw));
+    instance_tracker.track_all(prefab.spawn_all_config_instances(ecs, phys, tag_bank, draw));
 }
@@ -1,10 +1,11 @@
 #[cfg(feature = "confui")]
 use super::Popup;
-use super::{Comp, InstanceConfig, InstanceKey, PrefabKey};
+use super::{Comp, Config, InstanceConfig, InstanceKey, PrefabKey};
 use crate::{
     phys,
     world::{self, script},
 };
+use fxhash::FxHashMap;
 use glam::Vec2;
 
 #[cfg(feature = "confui")]
@@ -21,7 +22,17 @@ type ScannedTag = (usize, Vec2, InstanceKey);
 /// Tracks all of the spawned prefab instances
 /// so that we can reset them or clear them if need be.
 pub struct Tracker {
-    pub spawned: Vec<Tag>,
+    spawned: Vec<Tag>,
+
+    /// Positions in `spawned` of each prefab's instances.
+    by_prefab: FxHashMap<PrefabKey, Vec<usize>>,
+
+    /// Positions in `spawned` of each instance's Tag.
+    by_entity: FxHashMap<hecs::Entity, usize>,
+
+    /// Prefab names that have been looked up before. These are double checked against
+    /// the Config before they're used, in case the prefab was renamed or removed since.
+    by_name: FxHashMap<String, PrefabKey>,
 
     #[cfg(feature = "confui")]
     selector: selector::Selector,
@@ -37,7 +48,7 @@ pub struct Tracker {
     #[cfg(feature = "confui")]
     pub(super) popup: Popup,
 
-    #[cfg(feature = "confui")]
+    /// Set while the old instances are dying so that fresh ones can replace them.
     resetting: bool,
 }
 
@@ -52,16 +63,121 @@ impl Tracker {
         self.spawned.iter_mut().filter(|t| t.selected)
     }
 
+    pub fn tags(&self) -> &[Tag] {
+        &self.spawned
+    }
+
+    /// The Tag of the instance this Entity belongs to, if it belongs to one.
+    pub fn tag(&self, entity: hecs::Entity) -> Option<&Tag> {
+        self.by_entity.get(&entity).map(|&i| &self.spawned[i])
+    }
+
     pub fn instances_of(&self, pf_key: PrefabKey) -> impl Iterator<Item = &Tag> {
-        self.spawned.iter().filter(move |t| t.prefab_key == pf_key)
+        let spawned = &self.spawned;
+        self.by_prefab
+            .get(&pf_key)
+            .into_iter()
+            .flatten()
+            .map(move |&i| &spawned[i])
     }
 
+    /// Unlike `instances_of`, this has to search through every instance.
+    ///
+    /// If any of the Tags' entities are swapped out, `reindex` must be called afterwards.
     pub fn instances_of_mut(&mut self, pf_key: PrefabKey) -> impl Iterator<Item = &mut Tag> {
         self.spawned
             .iter_mut()
             .filter(move |t| t.prefab_key == pf_key)
     }
 
+    /// Finds the key of the prefab with this name, remembering it for next time.
+    pub fn prefab_key(&mut self, config: &Config, name: &str) -> Option<PrefabKey> {
+        if let Some(&key) = self.by_name.get(name) {
+            if config.fabs.get(key).filter(|pf| pf.name == name).is_some() {
+                return Some(key);
+            }
+        }
+
+        let (key, _) = config.by_name(name)?;
+        self.by_name.insert(name.to_string(), key);
+        Some(key)
+    }
+
+    pub fn track(&mut self, tag: Tag) {
+        let i = self.spawned.len();
+        self.by_prefab.entry(tag.prefab_key).or_default().push(i);
+        self.by_entity.insert(tag.entity, i);
+        self.spawned.push(tag);
+    }
+
+    pub fn track_all(&mut self, tags: impl IntoIterator<Item = Tag>) {
+        for tag in tags {
+            self.track(tag);
+        }
+    }
+
+    /// Stops tracking and returns every Tag the predicate returns true for.
+    pub fn untrack_where(&mut self, f: impl FnMut(&mut Tag) -> bool) -> Vec<Tag> {
+        let removed: Vec<Tag> = self.spawned.drain_filter(f).collect();
+        if !removed.is_empty() {
+            self.reindex();
+        }
+        removed
+    }
+
+    /// Rebuilds the indices from scratch.
+    /// Necessary after `spawned` is modified without going through `track`/`untrack_where`.
+    pub(super) fn reindex(&mut self) {
+        let Self {
+            spawned,
+            by_prefab,
+            by_entity,
+            ..
+        } = self;
+
+        by_prefab.values_mut().for_each(Vec::clear);
+        by_entity.clear();
+        for (i, tag) in spawned.iter().enumerate() {
+            by_prefab.entry(tag.prefab_key).or_default().push(i);
+            by_entity.insert(tag.entity, i);
+        }
+    }
+
+    pub fn resetting(&self) -> bool {
+        self.resetting
+    }
+
+    /// Kills every instance. Once they're all gone, `finish_reset`
+    /// respawns the ones from the Config.
+    pub fn start_reset(&mut self, dead: &mut world::Dead) {
+        self.resetting = true;
+        for tag in &self.spawned {
+            dead.mark(tag.entity);
+        }
+    }
+
+    /// Once every instance killed by `start_reset` is gone,
+    /// spawns all of the Config's instances anew.
+    pub fn finish_reset(
+        &mut self,
+        ecs: &mut hecs::World,
+        phys: &mut phys::CollisionWorld,
+        tag_bank: &mut script::TagBank,
+        config: &world::Config,
+    ) {
+        if !self.resetting || self.spawned.iter().any(|t| ecs.contains(t.entity)) {
+            return;
+        }
+
+        self.resetting = false;
+        self.untrack_where(|_| true);
+        self.track_all(
+            config
+                .prefab
+                .spawn_all_config_instances(ecs, phys, tag_bank, &config.draw),
+        );
+    }
+
     /// Use this function to spawn Instances that aren't a part of the config.
     pub fn spawn_dynamic(
         &mut self,
@@ -81,7 +197,7 @@ impl Tracker {
             comps,
             InstanceSource::Dynamic,
         );
-        self.spawned.push(tag.clone());
+        self.track(tag.clone());
         tag
     }
 }
@@ -28,10 +28,16 @@ pub fn overview_ui(ui: &mut egui::Ui, game: &mut Game) -> Option<()> {
 
     scan(game, cursor_pos);
 
+    let tracked = game.instance_tracker.spawned.len();
     selector::dev_ui(ui, game, cursor_pos);
 
     show_selected(ui, game);
-    recycle(game);
+    let recycled = recycle(game);
+
+    // pasting and undoing deletes push Tags, recycling swaps out their entities
+    if recycled || game.instance_tracker.spawned.len() != tracked {
+        game.instance_tracker.reindex();
+    }
 
     Some(())
 }
@@ -39,14 +45,9 @@ pub fn overview_ui(ui: &mut egui::Ui, game: &mut Game) -> Option<()> {
 fn reset_ui(
     ui: &mut egui::Ui,
     Game {
-        ecs,
-        phys,
-        tag_bank,
-        instance_tracker: Tracker {
-            resetting, spawned, ..
-        },
+        instance_tracker,
+        encounters,
         dead,
-        config: world::Config { prefab, draw, .. },
         ..
     }: &mut Game,
 ) {
@@ -55,18 +56,8 @@ fn reset_ui(
     if ui.button("Reset Instances").clicked
         || (is_key_down(KeyCode::LeftControl) && is_key_pressed(KeyCode::R))
     {
-        *resetting = true;
-        for tag in &*spawned {
-            dead.mark(tag.entity);
-        }
-    }
-
-    if *resetting {
-        if !spawned.iter().any(|t| ecs.contains(t.entity)) {
-            spawned.clear();
-            *resetting = false;
-            spawned.extend(prefab.spawn_all_config_instances(ecs, phys, tag_bank, draw));
-        }
+        instance_tracker.start_reset(dead);
+        encounters.reset();
     }
 }
 
@@ -205,7 +196,8 @@ fn recycle(
             },
         ..
     }: &mut Game,
-) {
+) -> bool {
+    let mut recycled = false;
     recycle_bin.drain_filter(|instance_key| {
         if let Some(tag) = spawned
             .iter_mut()
@@ -221,9 +213,11 @@ fn recycle(
                 new_tag.selected = tag.selected;
                 new_tag
             };
+            recycled = true;
             true
         } else {
             false
         }
     });
+    recycled
 }
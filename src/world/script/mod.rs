@@ -1,7 +1,10 @@
 use super::Game;
-use crate::{draw, phys, world};
+use crate::{combat, draw, phys, world};
 use glsp::prelude::*;
 
+pub mod sequence;
+use sequence::Sequence;
+
 const DEFAULT_BEHAVIOR: &[u8] = compile!("src/world/script/default_behavior.glsp");
 
 /// Scripts use Tags to find specific entities.
@@ -303,9 +306,8 @@ fn prefablib() -> GResult<()> {
                 config,
                 ..
             } = &mut *Game::borrow_mut();
-            let (pf_key, _) = config
-                .prefab
-                .by_name(&prefab_name.name())
+            let pf_key = instance_tracker
+                .prefab_key(&config.prefab, &prefab_name.name())
                 .ok_or_else(|| error!("no prefab with name {}", prefab_name))?;
 
             Ok(glsp::arr_from_iter(
@@ -327,9 +329,8 @@ fn prefablib() -> GResult<()> {
                 config,
                 ..
             } = &mut *Game::borrow_mut();
-            let (pf_key, _) = config
-                .prefab
-                .by_name(&prefab_name.name())
+            let pf_key = instance_tracker
+                .prefab_key(&config.prefab, &prefab_name.name())
                 .ok_or_else(|| error!("no prefab with name {}", prefab_name))?;
 
             instance_tracker
@@ -339,9 +340,129 @@ fn prefablib() -> GResult<()> {
         }),
     )?;
 
+    glsp::bind_rfn(
+        "instances",
+        rfn!(
+            |prefab_name: Sym, source: Option<Sym>| -> GResult<Instances> {
+                let Game {
+                    instance_tracker,
+                    config,
+                    ..
+                } = &mut *Game::borrow_mut();
+                let prefab_key = instance_tracker
+                    .prefab_key(&config.prefab, &prefab_name.name())
+                    .ok_or_else(|| error!("no prefab with name {}", prefab_name))?;
+
+                let source = match source {
+                    None => None,
+                    Some(s) if s == glsp::sym("config")? => Some(true),
+                    Some(s) if s == glsp::sym("dynamic")? => Some(false),
+                    Some(s) => bail!("Expected either 'config or 'dynamic, got {}", s),
+                };
+
+                Ok(Instances { prefab_key, source })
+            }
+        ),
+    )?;
+
+    glsp::bind_rfn(
+        "stat",
+        rfn!(|name: Sym, prefab_name: Option<Sym>| -> GResult<Val> {
+            let Game { stats, .. } = &*Game::borrow();
+            let per_prefab = |counts: &std::collections::BTreeMap<String, usize>| match prefab_name
+            {
+                Some(pf) => counts.get(&*pf.name()).copied().unwrap_or(0),
+                None => counts.values().sum(),
+            };
+
+            let count = match &*name.name() {
+                "time-alive" => return Ok(Val::Flo(stats.seconds_alive())),
+                "throws" => stats.throws,
+                "hits" => stats.hits,
+                "misses" => stats.misses,
+                "kills" => per_prefab(&stats.kills),
+                "damage" => per_prefab(&stats.damage_dealt),
+                other => bail!("Unknown stat: {}", other),
+            };
+            Ok(Val::Int(count as i32))
+        }),
+    )?;
+
     Ok(())
 }
 
+rdata! {
+    /// A live view of a prefab's instances; unlike `instances-of`, which returns
+    /// the instances at the time it's called, this can be kept around in a field
+    /// and it'll always reflect the instances that currently exist.
+    #[derive(Copy, Clone)]
+    pub struct Instances {
+        prefab_key: world::prefab::PrefabKey,
+        /// If Some, only instances that are (true) or aren't (false) from the Config.
+        source: Option<bool>,
+    }
+
+    meths {
+        "len": Self::len,
+        "all": Self::all,
+        "nearest": Self::nearest,
+    }
+}
+
+impl Instances {
+    fn each<T>(&self, mut f: impl FnMut(&world::prefab::instances::Tag) -> Option<T>) -> Vec<T> {
+        let Game {
+            instance_tracker, ..
+        } = &*Game::borrow();
+        instance_tracker
+            .instances_of(self.prefab_key)
+            .filter(|t| {
+                self.source
+                    .map_or(true, |config| config == t.instance_key().is_some())
+            })
+            .filter_map(|t| f(t))
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.each(|_| Some(())).len()
+    }
+
+    fn all(&self) -> GResult<Root<Arr>> {
+        glsp::arr_from_iter(self.each(|t| t.ent.clone()))
+    }
+
+    /// The instance closest to `pos`. If a predicate is supplied,
+    /// the closest instance for which the predicate returns true.
+    fn nearest(&self, V2(x, y): V2, pred: Option<Callable>) -> GResult<Option<RRoot<Ent>>> {
+        let pos = na::Vector2::new(x, y);
+        let mut found = {
+            let Game { ecs, phys, .. } = &*Game::borrow();
+            self.each(|t| {
+                let h = *ecs.get::<phys::PhysHandle>(t.entity).ok()?;
+                let loc = phys.collision_object(h)?.position().translation.vector;
+                Some(((loc - pos).magnitude_squared(), t.ent.clone()?))
+            })
+        };
+        found.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Greater));
+
+        // the Game can't be borrowed here, the predicate probably needs it
+        for (_, ent) in found {
+            match &pred {
+                Some(p) => {
+                    let keep: Val = glsp::call(p, &(&ent,))?;
+                    if keep.is_truthy() {
+                        return Ok(Some(ent));
+                    }
+                }
+                None => return Ok(Some(ent)),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
 #[test]
 fn slerp() {
     use glam::Vec2;
@@ -376,12 +497,24 @@ rdata! {
         set "rot": Self::set_rot,
         get "force": Self::force,
         set "force": Self::set_force,
+        get "velocity": Self::velocity,
+        set "velocity": Self::set_velocity,
         get "look-toward": Self::look_toward,
         set "look-toward": Self::set_look_toward,
         get "size": Self::size,
         set "size": Self::set_size,
         get "prefab": Self::prefab_name,
         get "anim-frame": Self::anim_frame,
+        get "art": Self::art,
+        set "art": Self::set_art,
+        get "anim-speed": Self::anim_speed,
+        set "anim-speed": Self::set_anim_speed,
+        "play-anim": Self::play_anim,
+        "status?": Self::has_status,
+        "apply-status": Self::apply_status,
+        "clear-status": Self::clear_status,
+        "pause-anim": Self::pause_anim,
+        "resume-anim": Self::resume_anim,
         "toggle-collision-whitelist": Self::toggle_collision_whitelist,
         "tagval": Self::tagval,
         "has-tag": Self::has_tag,
@@ -464,12 +597,15 @@ impl Ent {
         collide: phys::Collide,
         desired_state: Option<bool>,
     ) -> GResult<bool> {
+        let group = collide
+            .index()
+            .ok_or_else(|| error!("No collision group named {}", collide.0))?;
         let Game { ecs, phys, .. } = &mut *glsp::lib_mut();
 
         let c = collider!(ecs, phys, self.0)?;
         let mut groups = *c.collision_groups();
-        let state = desired_state.unwrap_or_else(|| !groups.is_member_of(collide as usize));
-        groups.modify_whitelist(collide as usize, state);
+        let state = desired_state.unwrap_or_else(|| !groups.is_member_of(group));
+        groups.modify_whitelist(group, state);
         c.set_collision_groups(groups);
 
         Ok(state)
@@ -499,15 +635,35 @@ impl Ent {
             })
     }
 
+    fn velocity(&self) -> V2 {
+        let Game { ecs, .. } = &*glsp::lib();
+        let vel = ecs.get::<phys::Velocity>(self.0);
+
+        match vel {
+            Ok(v) => V2(v.vec.x, v.vec.y),
+            Err(_) => V2(0.0, 0.0),
+        }
+    }
+
+    /// Ents without a Velocity are given one that never slows down.
+    fn set_velocity(&self, V2(x, y): V2) -> GResult<()> {
+        let Game { ecs, .. } = &mut *glsp::lib_mut();
+
+        ecs.get_mut::<phys::Velocity>(self.0)
+            .map(|mut v| v.vec = na::Vector2::new(x, y))
+            .or_else(|_| {
+                ecs.insert_one(self.0, phys::Velocity::new(na::Vector2::new(x, y)))
+                    .map_err(|e| error!("Couldn't set velocity on Ent {:#?}: {}", self.0, e))
+            })
+    }
+
     fn prefab<'a>(
         &self,
         config: &'a world::Config,
         instance_tracker: &world::prefab::InstanceTracker,
     ) -> GResult<&'a world::prefab::PrefabConfig> {
         Ok(&config.prefab.fabs[instance_tracker
-            .spawned
-            .iter()
-            .find(|x| x.entity == self.0)
+            .tag(self.0)
             .map(|x| x.prefab_key)
             .ok_or_else(|| error!("This Ent has no prefab"))?])
     }
@@ -597,6 +753,120 @@ impl Ent {
         Ok(af.current_frame(ss))
     }
 
+    fn art(&self) -> GResult<Sym> {
+        let Game { ecs, config, .. } = &*glsp::lib();
+        let looks = ecs
+            .get::<draw::Looks>(self.0)
+            .map_err(|e| error!("Couldn't get this Ent's looks: {}", e))?;
+
+        glsp::sym(config.draw.get(looks.art).name())
+    }
+
+    /// Swaps the art without touching the animation's progress.
+    fn set_art(&self, art: Val) -> GResult<()> {
+        let Game { ecs, config, .. } = &mut *glsp::lib_mut();
+        let ah = find_art(&config.draw, &art)?;
+        ecs.get_mut::<draw::Looks>(self.0)
+            .map_err(|e| error!("Couldn't get this Ent's looks: {}", e))?
+            .art = ah;
+
+        if config.draw.get(ah).spritesheet.is_some()
+            && ecs.get::<draw::AnimationFrame>(self.0).is_err()
+        {
+            ecs.insert_one(self.0, draw::AnimationFrame(0))
+                .map_err(|e| error!("Couldn't animate Ent {:#?}: {}", self.0, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Swaps the art and plays its spritesheet from the beginning.
+    /// Accepts `:loop` and `:speed` options, i.e. `(.play-anim ent 'slime-die :loop #f)`
+    fn play_anim(&self, art: Val, opts: &[Val]) -> GResult<()> {
+        let mut playback = draw::Playback::default();
+        if opts.len() % 2 != 0 {
+            bail!("play-anim options should come in pairs, like :loop #f")
+        }
+        for pair in opts.chunks(2) {
+            let opt = Sym::from_val(&pair[0])?;
+            match &*opt.name() {
+                ":loop" => playback.looping = bool::from_val(&pair[1])?,
+                ":speed" => playback.speed = Num::from_val(&pair[1])?.into_f32(),
+                other => bail!("Unknown play-anim option: {}", other),
+            }
+        }
+
+        let Game { ecs, config, .. } = &mut *glsp::lib_mut();
+        let ah = find_art(&config.draw, &art)?;
+        if config.draw.get(ah).spritesheet.is_none() {
+            bail!(
+                "Can't play {}, it has no spritesheet",
+                config.draw.get(ah).file
+            );
+        }
+        ecs.get_mut::<draw::Looks>(self.0)
+            .map_err(|e| error!("Couldn't get this Ent's looks: {}", e))?
+            .art = ah;
+        ecs.insert(self.0, (draw::AnimationFrame(0), playback))
+            .map_err(|e| error!("Couldn't play animation on Ent {:#?}: {}", self.0, e))
+    }
+
+    fn playback<T>(&self, f: impl FnOnce(&mut draw::Playback) -> T) -> GResult<T> {
+        let Game { ecs, .. } = &mut *glsp::lib_mut();
+        if let Ok(mut pb) = ecs.get_mut::<draw::Playback>(self.0) {
+            return Ok(f(&mut pb));
+        }
+
+        let mut pb = draw::Playback::default();
+        let out = f(&mut pb);
+        ecs.insert_one(self.0, pb)
+            .map_err(|e| error!("Couldn't control Ent {:#?}'s animation: {}", self.0, e))?;
+        Ok(out)
+    }
+
+    fn pause_anim(&self) -> GResult<()> {
+        self.playback(|pb| pb.paused = true)
+    }
+
+    fn resume_anim(&self) -> GResult<()> {
+        self.playback(|pb| pb.paused = false)
+    }
+
+    fn anim_speed(&self) -> f32 {
+        let Game { ecs, .. } = &*glsp::lib();
+        ecs.get::<draw::Playback>(self.0)
+            .map(|pb| pb.speed)
+            .unwrap_or(1.0)
+    }
+
+    fn set_anim_speed(&self, speed: Num) -> GResult<()> {
+        self.playback(|pb| pb.speed = speed.into_f32())
+    }
+
+    fn has_status(&self, kind: combat::StatusKind) -> bool {
+        let Game { ecs, .. } = &*glsp::lib();
+        ecs.get::<combat::StatusEffects>(self.0)
+            .map_or(false, |fx| fx.has(kind))
+    }
+
+    /// i.e. `(.apply-status ent 'slow 120 0.5)` halves an Ent's speed for two seconds.
+    fn apply_status(&self, kind: combat::StatusKind, frames: u16, strength: Option<Num>) {
+        let Game { ecs, .. } = &mut *glsp::lib_mut();
+        let strength = strength.map(|s| s.into_f32()).unwrap_or(1.0);
+        combat::status::apply_status(
+            ecs,
+            self.0,
+            combat::StatusEffect::new(kind, frames, strength),
+        );
+    }
+
+    fn clear_status(&self, kind: combat::StatusKind) {
+        let Game { ecs, .. } = &mut *glsp::lib_mut();
+        if let Ok(mut fx) = ecs.get_mut::<combat::StatusEffects>(self.0) {
+            fx.remove(kind);
+        }
+    }
+
     fn tag(&self, tag: Sym) -> GResult<Option<Val>> {
         let Game { tag_bank, .. } = &*glsp::lib();
         Ok(tag_bank
@@ -622,6 +892,17 @@ impl Ent {
     }
 }
 
+/// Art can be referred to by file name, or by file name without the extension.
+fn find_art(draw: &draw::Config, art: &Val) -> GResult<draw::ArtHandle> {
+    let name = match art {
+        Val::Sym(s) => s.name().to_string(),
+        Val::Str(s) => s.to_string(),
+        other => bail!("expected art name, received {}", other.a_type_name()),
+    };
+    draw.find_art(&name)
+        .ok_or_else(|| error!("no art by name of {}", name))
+}
+
 syms! {
     pub struct Syms {
         update: "update",
@@ -631,6 +912,14 @@ syms! {
         message: "message",
         death: "death",
         init: "init",
+        anim_finished: "anim-finished",
+        frame: "frame",
+        weapon_pickup: "weapon-pickup?",
+        encounter_complete: "encounter-complete",
+        enter: "enter",
+        exit: "exit",
+        trigger_enter: "trigger-enter",
+        trigger_exit: "trigger-exit",
     }
 }
 
@@ -638,6 +927,7 @@ lib! {
     pub struct Intake {
         pub needs_script: Vec<(hecs::Entity, String)>,
         pub messages: Vec<(hecs::Entity, Val)>,
+        pub sequences: Vec<Sequence>,
     }
 }
 
@@ -646,6 +936,7 @@ impl Intake {
         Self {
             needs_script: Vec::with_capacity(1000),
             messages: Vec::with_capacity(1000),
+            sequences: Vec::with_capacity(100),
         }
     }
 }
@@ -701,12 +992,43 @@ lib! {
     pub struct Cache {
         syms: Syms,
         pub new_collisions: Vec<(hecs::Entity, hecs::Entity)>,
+        /// Entities whose spritesheets just reached their last frame, and the art's name.
+        pub anim_finished: Vec<(hecs::Entity, Sym)>,
+        /// Entities whose spritesheets just switched to a new frame, and that frame.
+        pub frame_changes: Vec<(hecs::Entity, usize)>,
+        /// Entities that are stunned, and so shouldn't have `update` called on them.
+        pub stunned: fxhash::FxHashSet<hecs::Entity>,
+        /// Thrown weapons the player is close enough to pick up,
+        /// if the `weapon-pickup?` hook doesn't object.
+        pub weapon_pickups: Vec<hecs::Entity>,
+        /// The names of Encounters whose last wave was just cleared.
+        pub completed_encounters: Vec<String>,
+        /// Entities whose Triggers something just went into, and what went in.
+        pub triggers_entered: Vec<(hecs::Entity, hecs::Entity)>,
+        /// Entities whose Triggers something just left, and what left.
+        pub triggers_exited: Vec<(hecs::Entity, hecs::Entity)>,
         classes: Vec<Root<Class>>,
         scripts: Vec<(Root<Obj>, RRoot<Ent>)>,
+        sequences: Vec<Sequence>,
+        coro_fns: sequence::CoroFns,
         intake: Intake,
     }
 }
 
+/// Sorts events out by the Entity they happened to.
+fn by_entity<T: Copy>(events: &[(hecs::Entity, T)]) -> fxhash::FxHashMap<hecs::Entity, Vec<T>> {
+    let mut sorted = fxhash::FxHashMap::<_, Vec<T>>::default();
+    for &(e, event) in events {
+        sorted.entry(e).or_default().push(event);
+    }
+    sorted
+}
+
+/// What happened to `e`, out of the events sorted by `by_entity`.
+fn events_of<T>(sorted: &fxhash::FxHashMap<hecs::Entity, Vec<T>>, e: hecs::Entity) -> &[T] {
+    sorted.get(&e).map_or(&[][..], Vec::as_slice)
+}
+
 impl Cache {
     pub fn new(classes: &Val) -> GResult<Self> {
         prefablib()?;
@@ -716,6 +1038,13 @@ impl Cache {
             "Vec2",
             rfn!(|x: Num, y: Num| V2(x.into_f32(), y.into_f32())),
         )?;
+        glsp::bind_rfn(
+            "set-checkpoint",
+            rfn!(|pos: V2| {
+                let V2(x, y) = pos;
+                Game::borrow_mut().player.checkpoint = na::Vector2::new(x, y);
+            }),
+        )?;
 
         Ok(Self {
             classes: FromVal::from_val(classes)?,
@@ -724,6 +1053,15 @@ impl Cache {
             // than 1000 scripted entities in a single frame
             scripts: Vec::with_capacity(1000),
             new_collisions: Vec::with_capacity(1000),
+            anim_finished: Vec::with_capacity(100),
+            frame_changes: Vec::with_capacity(1000),
+            stunned: Default::default(),
+            weapon_pickups: Vec::with_capacity(1),
+            completed_encounters: Vec::with_capacity(1),
+            triggers_entered: Vec::with_capacity(100),
+            triggers_exited: Vec::with_capacity(100),
+            sequences: Vec::with_capacity(100),
+            coro_fns: sequence::CoroFns::new()?,
             intake: Intake::new(),
         })
     }
@@ -788,10 +1126,21 @@ impl Cache {
             syms,
             classes,
             new_collisions,
-            intake: Intake {
-                needs_script,
-                messages,
-            },
+            anim_finished,
+            frame_changes,
+            stunned,
+            weapon_pickups,
+            completed_encounters,
+            triggers_entered,
+            triggers_exited,
+            sequences,
+            coro_fns,
+            intake:
+                Intake {
+                    needs_script,
+                    messages,
+                    sequences: new_sequences,
+                },
             ..
         } = self;
 
@@ -809,6 +1158,13 @@ impl Cache {
                 .ok()
         }));
 
+        // sorted out once up front, so that each script doesn't sift through everyone's events
+        let collisions_by = by_entity(new_collisions);
+        let entered_by = by_entity(triggers_entered);
+        let exited_by = by_entity(triggers_exited);
+        let frame_changes_by = by_entity(frame_changes);
+        let anims_finished_by = by_entity(anim_finished);
+
         for (behavior, ent) in scripts {
             let hecs_entity = ent.borrow().0;
             let ent = &*ent;
@@ -817,7 +1173,7 @@ impl Cache {
                 call! { behavior.syms.message(ent, message) }
             }
 
-            for (_, collided_with) in new_collisions.iter().filter(|&&(e1, _)| e1 == hecs_entity) {
+            for collided_with in events_of(&collisions_by, hecs_entity) {
                 let class = behavior.class();
                 let collision = &syms.collision;
                 let ro = behavior
@@ -832,16 +1188,115 @@ impl Cache {
                 call!(class, collision, ro);
             }
 
-            call! { behavior.syms.update(ent,) }
+            for &other in events_of(&entered_by, hecs_entity) {
+                match glsp::rroot(Ent(other)) {
+                    Ok(other) => {
+                        call! { behavior.syms.enter(ent, other) }
+                    }
+                    Err(e) => eprn!("couldn't allocate Ent for enter: {}", e),
+                }
+            }
+
+            for &other in events_of(&exited_by, hecs_entity) {
+                match glsp::rroot(Ent(other)) {
+                    Ok(other) => {
+                        call! { behavior.syms.exit(ent, other) }
+                    }
+                    Err(e) => eprn!("couldn't allocate Ent for exit: {}", e),
+                }
+            }
+
+            for &frame in events_of(&frame_changes_by, hecs_entity) {
+                call! { behavior.syms.frame(ent, frame) }
+            }
+
+            for &art in events_of(&anims_finished_by, hecs_entity) {
+                call! { behavior.syms.anim_finished(ent, art) }
+            }
+
+            if !stunned.contains(&hecs_entity) {
+                call! { behavior.syms.update(ent,) }
+            }
+        }
+
+        sequences.extend(new_sequences.drain(..));
+        sequences.drain_filter(|seq| !seq.resume(messages, coro_fns));
+
+        // scripts can define `weapon-pickup?` to decide when the weapon can be picked up
+        for wep_ent in weapon_pickups.drain(..) {
+            let allowed = match glsp::global::<_, Callable>(syms.weapon_pickup) {
+                Ok(hook) => glsp::rroot(Ent(wep_ent))
+                    .and_then(|ent| {
+                        let allowed: Val = glsp::call(&hook, &(ent,))?;
+                        Ok(allowed.is_truthy())
+                    })
+                    .unwrap_or_else(|e| {
+                        eprn!("weapon-pickup? failed: {}", e);
+                        false
+                    }),
+                Err(_) => true,
+            };
+            if allowed {
+                world::player::recover_weapon(&mut *Game::borrow_mut(), wep_ent);
+            }
+        }
+
+        // scripts can define `encounter-complete` to find out when an Encounter is over
+        if let Ok(hook) = glsp::global::<_, Callable>(syms.encounter_complete) {
+            for name in completed_encounters.iter() {
+                let ro = glsp::sym(name).and_then(|name| {
+                    let _: Val = glsp::call(&hook, &(name,))?;
+                    Ok(())
+                });
+                if let Err(e) = ro {
+                    eprn!("encounter-complete failed for {}: {}", name, e);
+                }
+            }
+        }
+
+        // scripts can define `trigger-enter` and `trigger-exit` to listen in on every Trigger
+        let trigger_hooks = [
+            (&*triggers_entered, syms.trigger_enter),
+            (&*triggers_exited, syms.trigger_exit),
+        ];
+        for &(events, hook_name) in trigger_hooks.iter() {
+            if let Ok(hook) = glsp::global::<_, Callable>(hook_name) {
+                for &(trigger_ent, other_ent) in events.iter() {
+                    let ro = glsp::rroot(Ent(trigger_ent)).and_then(|trigger| {
+                        let other = glsp::rroot(Ent(other_ent))?;
+                        let _: Val = glsp::call(&hook, &(trigger, other))?;
+                        Ok(())
+                    });
+                    if let Err(e) = ro {
+                        eprn!("{} failed: {}", hook_name, e);
+                    }
+                }
+            }
         }
 
         new_collisions.clear();
+        anim_finished.clear();
+        frame_changes.clear();
+        stunned.clear();
+        completed_encounters.clear();
+        triggers_entered.clear();
+        triggers_exited.clear();
         needs_script.clear();
         messages.clear();
     }
 
     pub fn cleanup(&mut self) {
-        let Self { scripts, syms, .. } = self;
+        let Self {
+            scripts,
+            syms,
+            sequences,
+            ..
+        } = self;
+
+        sequences.drain_filter(|seq| {
+            seq.ent()
+                .map_or(false, |e| Game::borrow_mut().dead.is_marked(e))
+        });
 
         for (behavior, ent) in
             scripts.drain_filter(|(_, e)| Game::borrow_mut().dead.is_marked(e.borrow().0))
@@ -0,0 +1,137 @@
+use super::Ent;
+use glsp::prelude::*;
+
+const SEQUENCE_HELPERS: &[u8] = compile!("src/world/script/sequence.glsp");
+
+/// Defines `run-sequence` and `await-message`, as well as the `wait`, `wait-until`, `move-to`
+/// and `wait-message` macros. This must be called before any scripts using those macros are loaded.
+pub fn prelude() -> GResult<()> {
+    glsp::bind_rfn(
+        "await-message",
+        rfn!(|name: Sym| -> AwaitMessage { AwaitMessage(name) }),
+    )?;
+    glsp::load_compiled(SEQUENCE_HELPERS)?;
+    glsp::bind_rfn(
+        "run-sequence",
+        rfn!(|f: Val, ent: Option<RRoot<Ent>>| -> GResult<()> {
+            let coro: Root<Coro> = match f {
+                Val::Coro(coro) => coro,
+                other => glsp::call(&Callable::from_val(&other)?, &())?,
+            };
+            super::Intake::borrow_mut().sequences.push(Sequence {
+                coro,
+                ent,
+                awaiting: None,
+            });
+            Ok(())
+        }),
+    )?;
+
+    Ok(())
+}
+
+rdata! {
+    /// What `wait-message` yields, so that a Sequence can tell waiting for a message
+    /// apart from any other value a script might happen to yield.
+    #[derive(Copy, Clone)]
+    pub struct AwaitMessage(Sym);
+
+    meths {
+        get "name": Self::name,
+    }
+}
+impl AwaitMessage {
+    fn name(&self) -> Sym {
+        self.0
+    }
+}
+
+/// A coroutine that is resumed once each frame until it finishes.
+///
+/// Multi-step behaviors can be written top to bottom this way, instead of as
+/// states that have to work out how far along they are every frame.
+pub struct Sequence {
+    coro: Root<Coro>,
+    /// Sequences started on an Ent stop when it dies, and can wait for its messages.
+    ent: Option<RRoot<Ent>>,
+    /// The name of the message this Sequence is waiting for, if any.
+    awaiting: Option<Sym>,
+}
+impl Sequence {
+    pub fn ent(&self) -> Option<hecs::Entity> {
+        self.ent.as_ref().map(|e| e.borrow().0)
+    }
+
+    /// Resumes the coroutine if it isn't waiting on a message it hasn't gotten yet.
+    /// Returns false if the Sequence is finished and should be dropped.
+    pub fn resume(&mut self, messages: &[(hecs::Entity, Val)], fns: &CoroFns) -> bool {
+        let arg = match self.awaiting {
+            None => None,
+            Some(name) => {
+                let ent = match self.ent() {
+                    Some(e) => e,
+                    None => {
+                        eprn!("Sequence waiting for {} message, but has no Ent", name);
+                        return false;
+                    }
+                };
+                match messages
+                    .iter()
+                    .find(|(e, m)| *e == ent && message_name(m) == Some(name))
+                {
+                    Some((_, m)) => Some(m.clone()),
+                    None => return true,
+                }
+            }
+        };
+
+        let yielded: GResult<Val> = match arg {
+            Some(m) => glsp::call(&fns.run, &(&self.coro, m)),
+            None => glsp::call(&fns.run, &(&self.coro,)),
+        };
+        self.awaiting = match yielded {
+            Ok(val) => RRoot::<AwaitMessage>::from_val(&val)
+                .ok()
+                .map(|awaiting| awaiting.borrow().0),
+            Err(e) => {
+                eprn!("Couldn't resume sequence: {}", e);
+                return false;
+            }
+        };
+
+        match glsp::call::<_, _, Sym>(&fns.state, &(&self.coro,)) {
+            Ok(state) => state != fns.finished && state != fns.poisoned,
+            Err(e) => {
+                eprn!("Couldn't get sequence state: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// Messages are either a lone symbol, or an array starting with one.
+fn message_name(message: &Val) -> Option<Sym> {
+    match message {
+        Val::Sym(s) => Some(*s),
+        Val::Arr(a) => a.get(0).ok(),
+        _ => None,
+    }
+}
+
+/// The builtins needed to drive Sequences, looked up once ahead of time.
+pub struct CoroFns {
+    run: Callable,
+    state: Callable,
+    finished: Sym,
+    poisoned: Sym,
+}
+impl CoroFns {
+    pub fn new() -> GResult<Self> {
+        Ok(Self {
+            run: glsp::global("coro-run")?,
+            state: glsp::global("coro-state")?,
+            finished: glsp::sym("finished")?,
+            poisoned: glsp::sym("poisoned")?,
+        })
+    }
+}
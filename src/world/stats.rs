@@ -0,0 +1,182 @@
+use super::prefab;
+use macroquad::*;
+use std::collections::BTreeMap;
+
+/// How big the text in the corner of the screen is.
+const HUD_SIZE: f32 = 24.0;
+/// How big the text summarizing a run is.
+const SUMMARY_SIZE: f32 = 32.0;
+/// Frames per second, for turning frames spent alive into seconds.
+const FPS: f32 = 60.0;
+/// How many frames the player can be alive for between writes of their Stats,
+/// so that closing the game never loses much more than this.
+#[cfg(not(target_arch = "wasm32"))]
+const AUTOSAVE_FRAMES: u64 = 30 * 60;
+
+/// The name of the prefab this Entity is an instance of, if it's an instance of one.
+pub fn prefab_name<'a>(
+    tracker: &prefab::InstanceTracker,
+    config: &'a prefab::Config,
+    e: hecs::Entity,
+) -> Option<&'a str> {
+    let key = tracker.tag(e)?.prefab_key;
+    config.fabs.get(key).map(|pf| pf.name.as_str())
+}
+
+/// Keeps count of how the player is doing over the course of a single life,
+/// so that different balance changes can be compared.
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct Stats {
+    pub throws: usize,
+    /// Throws that hurt at least one thing.
+    pub hits: usize,
+    /// Throws that didn't hurt anything before they were picked back up or thrown again.
+    pub misses: usize,
+    /// Damage dealt to instances of each prefab.
+    pub damage_dealt: BTreeMap<String, usize>,
+    /// How many instances of each prefab have run out of health.
+    pub kills: BTreeMap<String, usize>,
+    pub frames_alive: u64,
+
+    /// The weapon thrown most recently, and whether or not it has hurt anything yet.
+    #[serde(skip)]
+    in_flight: Option<(hecs::Entity, bool)>,
+    /// Where these Stats are written, chosen the first time they are,
+    /// so that every write over the course of a life lands in the same file.
+    #[serde(skip)]
+    file: Option<String>,
+}
+impl Stats {
+    pub fn seconds_alive(&self) -> f32 {
+        self.frames_alive as f32 / FPS
+    }
+
+    /// Called once a frame while the player is alive.
+    pub fn tick(&mut self) {
+        self.frames_alive += 1;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.frames_alive % AUTOSAVE_FRAMES == 0 {
+            self.save();
+        }
+    }
+
+    pub fn total_kills(&self) -> usize {
+        self.kills.values().sum()
+    }
+
+    pub fn total_damage_dealt(&self) -> usize {
+        self.damage_dealt.values().sum()
+    }
+
+    /// Called whenever the player throws their weapon.
+    pub fn thrown(&mut self, weapon: hecs::Entity) {
+        self.land();
+        self.throws += 1;
+        self.in_flight = Some((weapon, false));
+    }
+
+    /// Called when a thrown weapon is back in the player's hands,
+    /// at which point it's too late for it to hit anything.
+    pub fn land(&mut self) {
+        if let Some((_, false)) = self.in_flight.take() {
+            self.misses += 1;
+        }
+    }
+
+    /// Called whenever `hurtful`, one of the player's weapons, deals `amount` damage to `victim`.
+    pub fn damaged(&mut self, hurtful: hecs::Entity, victim: Option<&str>, amount: usize) {
+        if let Some((weapon, hit)) = &mut self.in_flight {
+            if *weapon == hurtful && !*hit {
+                *hit = true;
+                self.hits += 1;
+            }
+        }
+        if let Some(name) = victim {
+            *self.damage_dealt.entry(name.to_string()).or_default() += amount;
+        }
+    }
+
+    pub fn killed(&mut self, victim: Option<&str>) {
+        if let Some(name) = victim {
+            *self.kills.entry(name.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Writes these Stats to a file in the `stats` folder, named after the time they were
+    /// first written. Later writes overwrite that file, so each life gets one file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&mut self) {
+        let path = self
+            .file
+            .get_or_insert_with(|| {
+                let secs = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+                format!("stats/{}.ron", secs)
+            })
+            .clone();
+
+        let written = std::fs::create_dir_all("stats")
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                ron::ser::to_string_pretty(self, Default::default()).map_err(|e| e.to_string())
+            })
+            .and_then(|s| std::fs::write(&path, s).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            error!("couldn't write stats to {}: {}", path, e);
+        }
+    }
+
+    /// A line of stats in the corner of the screen,
+    /// and a summary of the whole run if `summarize` is true.
+    pub fn draw(&self, summarize: bool) {
+        set_default_camera();
+
+        draw_text(
+            &format!(
+                "throws {}  hits {}  kills {}",
+                self.throws,
+                self.hits,
+                self.total_kills()
+            ),
+            10.0,
+            10.0,
+            HUD_SIZE,
+            WHITE,
+        );
+
+        if !summarize {
+            return;
+        }
+
+        let accuracy = match self.hits + self.misses {
+            0 => 0.0,
+            n => self.hits as f32 / n as f32 * 100.0,
+        };
+        let mut lines = vec![
+            format!("alive for {:.1} seconds", self.seconds_alive()),
+            format!(
+                "{} throws, {} hits, {} misses ({:.0}% accuracy)",
+                self.throws, self.hits, self.misses, accuracy
+            ),
+            format!("{} damage dealt", self.total_damage_dealt()),
+        ];
+        lines.extend(
+            self.kills
+                .iter()
+                .map(|(name, count)| format!("{} x{}", name, count)),
+        );
+
+        let top = screen_height() / 2.0 - lines.len() as f32 * SUMMARY_SIZE / 2.0;
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(
+                line,
+                screen_width() / 4.0,
+                top + i as f32 * SUMMARY_SIZE,
+                SUMMARY_SIZE,
+                WHITE,
+            );
+        }
+    }
+}